@@ -0,0 +1,230 @@
+use sara_level::prelude::*;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::ExitCode;
+use thiserror::Error;
+
+const USAGE: &str = "usage:
+    levelc info <file>...             print a summary of each level
+    levelc check <file>...            validate each level against the tile registry, exit 1 if any has issues
+    levelc convert <input> <output>   convert between .sbc and .sbc.json
+    levelc chain [dir]                validate the next chain of every level in dir";
+
+#[derive(Error, Debug)]
+enum LevelcError {
+    #[error("{0}: unknown level format, expected .sbc or .sbc.json")]
+    UnknownFormat(String),
+    #[error("{0}: {1}")]
+    Asset(String, LevelAssetError),
+}
+
+enum Command {
+    Info(Vec<String>),
+    Check(Vec<String>),
+    Convert(String, String),
+    Chain(String),
+}
+impl Command {
+    fn parse(mut args: impl Iterator<Item = String>) -> Option<Self> {
+        let command = args.next()?;
+        let paths = args.collect::<Vec<_>>();
+        match (command.as_str(), paths.len()) {
+            ("info", 1..) => Some(Self::Info(paths)),
+            ("check", 1..) => Some(Self::Check(paths)),
+            ("convert", 2) => Some(Self::Convert(paths[0].clone(), paths[1].clone())),
+            ("chain", 0) => Some(Self::Chain(
+                LevelAsset::data_dir().to_string_lossy().into_owned(),
+            )),
+            ("chain", 1) => Some(Self::Chain(paths[0].clone())),
+            _ => None,
+        }
+    }
+
+    fn format(path: &str) -> Result<LevelFormat, LevelcError> {
+        LevelFormat::from_path(path).ok_or_else(|| LevelcError::UnknownFormat(path.to_string()))
+    }
+
+    fn read(path: &str) -> Result<(LevelFormat, Vec<u8>, LevelAsset), LevelcError> {
+        let format = Self::format(path)?;
+        let bytes =
+            std::fs::read(path).map_err(|err| LevelcError::Asset(path.to_string(), err.into()))?;
+        let asset = LevelAsset::decode(&bytes, format)
+            .map_err(|err| LevelcError::Asset(path.to_string(), err))?;
+        Ok((format, bytes, asset))
+    }
+
+    fn info(path: &str) -> Result<(), LevelcError> {
+        let (format, bytes, asset) = Self::read(path)?;
+        println!("{path}");
+        match format {
+            LevelFormat::Binary => println!(
+                "  format: {} v{}",
+                format.name(),
+                LevelCodec::version(&bytes).unwrap_or_default()
+            ),
+            LevelFormat::Text => println!("  format: {}", format.name()),
+        }
+        println!("  rows: {}", asset.rows);
+        println!("  cols: {}", asset.cols);
+        println!("  tiles: {}", asset.tiles().count());
+        for layer in &asset.layers {
+            let mut kinds = BTreeMap::<&str, usize>::new();
+            for descriptor in &layer.data {
+                *kinds.entry(&descriptor.kind).or_default() += 1;
+            }
+            let counts = kinds
+                .iter()
+                .map(|(kind, count)| format!("{kind} {count}"))
+                .collect::<Vec<_>>();
+            println!(
+                "  layer {}: z {}, parallax {}, {}, {} tiles ({})",
+                layer.name,
+                layer.z,
+                layer.parallax,
+                if layer.collider {
+                    "collider"
+                } else {
+                    "decorative"
+                },
+                layer.data.len(),
+                counts.join(", ")
+            );
+        }
+        println!("  entry: {:?}", asset.entry);
+        println!(
+            "  bounds: kill plane {}px below, side walls {}",
+            asset.kill_depth,
+            if asset.side_walls { "on" } else { "off" }
+        );
+        if let Some(gravity) = asset.gravity {
+            println!("  gravity: {gravity} px/s^2");
+        }
+        if !asset.abilities.is_empty() {
+            let names = asset.abilities.iter().map(|ability| ability.name());
+            println!("  abilities: {}", names.collect::<Vec<_>>().join(", "));
+        }
+        match asset.next {
+            Some(next) => println!("  next: {next}"),
+            None => println!("  next: none"),
+        }
+        for (index, platform) in asset.platforms.iter().enumerate() {
+            println!(
+                "  platform {index}: {} x{}, {} waypoints, {} at {} px/s, wait {}s",
+                platform.kind,
+                platform.width,
+                platform.waypoints.len(),
+                platform.mode.name(),
+                platform.speed,
+                platform.wait
+            );
+        }
+        for (index, enemy) in asset.enemies.iter().enumerate() {
+            let params = match enemy.kind {
+                EnemyKind::Wolf {
+                    patrol,
+                    speed,
+                    sight,
+                } => format!("patrol {patrol}px at {speed} px/s, sight {sight}px"),
+                EnemyKind::Bat {
+                    pattern,
+                    range,
+                    speed,
+                } => format!("{} over {range}px at {speed} px/s", pattern.name()),
+            };
+            println!(
+                "  enemy {index}: {} at {:?}, {params}",
+                enemy.kind.name(),
+                enemy.position
+            );
+        }
+        for (index, checkpoint) in asset.checkpoints.iter().enumerate() {
+            println!("  checkpoint {index}: {checkpoint:?}");
+        }
+        for (index, pickup) in asset.pickups.iter().enumerate() {
+            println!(
+                "  pickup {index}: {} at {:?}",
+                pickup.ability.name(),
+                pickup.position
+            );
+        }
+        Ok(())
+    }
+
+    fn registry() -> Result<TileRegistry, LevelcError> {
+        let path = TileRegistry::file_path();
+        TileRegistry::read(Path::new(&path)).map_err(|err| LevelcError::Asset(path, err))
+    }
+
+    fn check(path: &str) -> Result<bool, LevelcError> {
+        let (_, _, asset) = Self::read(path)?;
+        let dir = Path::new(path).parent().unwrap_or(Path::new("."));
+        let issues = asset.validate(dir, &Self::registry()?);
+        if issues.is_empty() {
+            println!("{path}: ok");
+        }
+        for issue in &issues {
+            println!("{path}: {issue}");
+        }
+        Ok(issues.is_empty())
+    }
+
+    fn convert(input: &str, output: &str) -> Result<(), LevelcError> {
+        let (_, _, asset) = Self::read(input)?;
+        let bytes = asset
+            .encode(Self::format(output)?)
+            .map_err(|err| LevelcError::Asset(output.to_string(), err))?;
+        std::fs::write(output, bytes)
+            .map_err(|err| LevelcError::Asset(output.to_string(), err.into()))
+    }
+
+    fn chain(dir: &str) -> Result<bool, LevelcError> {
+        let pack = LevelPack::load(Path::new(dir))
+            .map_err(|err| LevelcError::Asset(dir.to_string(), err))?;
+        let issues = pack.validate_chain();
+        if issues.is_empty() {
+            println!("{dir}: {} levels chained ok", pack.levels.len());
+        }
+        for issue in &issues {
+            println!("{dir}: {issue}");
+        }
+        Ok(issues.is_empty())
+    }
+
+    // 0 when everything is fine, 1 when a level has issues, 2 when a file could not be processed
+    fn run(self) -> ExitCode {
+        let mut failed = false;
+        let mut invalid = false;
+        let mut report = |result: Result<bool, LevelcError>| match result {
+            Ok(valid) => invalid |= !valid,
+            Err(err) => {
+                eprintln!("{err}");
+                failed = true;
+            }
+        };
+        match self {
+            Self::Info(paths) => paths
+                .iter()
+                .for_each(|path| report(Self::info(path).map(|_| true))),
+            Self::Check(paths) => paths.iter().for_each(|path| report(Self::check(path))),
+            Self::Convert(input, output) => report(Self::convert(&input, &output).map(|_| true)),
+            Self::Chain(dir) => report(Self::chain(&dir)),
+        }
+        if failed {
+            ExitCode::from(2)
+        } else if invalid {
+            ExitCode::from(1)
+        } else {
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match Command::parse(std::env::args().skip(1)) {
+        Some(command) => command.run(),
+        None => {
+            eprintln!("{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
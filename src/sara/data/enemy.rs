@@ -0,0 +1,59 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+use strum::{EnumIter, IntoEnumIterator};
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, EnumIter)]
+pub enum EnemyAsepriteType {
+    Wolf,
+    Bat,
+}
+impl EnemyAsepriteType {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Wolf => "wolf",
+            Self::Bat => "bat",
+        }
+    }
+
+    pub const fn texture_size(&self) -> UVec2 {
+        match self {
+            Self::Wolf => UVec2::new(64, 32),
+            Self::Bat => UVec2::new(34, 34),
+        }
+    }
+
+    // both sheets are five frames wide, one animation per row
+    pub fn layout(&self) -> TextureAtlasLayout {
+        TextureAtlasLayout::from_grid(self.texture_size(), 5, 3, None, None)
+    }
+}
+
+#[derive(Resource)]
+pub struct EnemyResource {
+    pub texture_atlas_handles:
+        HashMap<EnemyAsepriteType, (Handle<Image>, Handle<TextureAtlasLayout>)>,
+}
+impl EnemyResource {
+    pub const TEXTURE_BASE_PATH: &'static str = "images/enemy/";
+    pub const SUFFIX: &'static str = ".png";
+
+    fn texture_path(texture_name: &str) -> String {
+        Self::TEXTURE_BASE_PATH.to_string() + texture_name + Self::SUFFIX
+    }
+
+    pub fn new(asset_server: &Res<AssetServer>) -> Self {
+        let mut texture_atlas_handles = HashMap::new();
+        for aseprite_type in EnemyAsepriteType::iter() {
+            texture_atlas_handles.insert(
+                aseprite_type,
+                (
+                    asset_server.load(Self::texture_path(aseprite_type.name())),
+                    asset_server.add(aseprite_type.layout()),
+                ),
+            );
+        }
+        Self {
+            texture_atlas_handles,
+        }
+    }
+}
@@ -1,3 +1,4 @@
+pub mod enemy;
 pub mod level;
 pub mod player;
 pub mod prelude;
@@ -12,14 +13,8 @@ impl DataManager {
         mut level_init: EventReader<LevelInit>,
         asset_server: Res<AssetServer>,
     ) {
-        let texture_handle = asset_server.load(LevelResource::TEXTURE_ATLAS_PATH);
-        let layout_handle = asset_server.add(TextureAtlasLayout::from_grid(
-            LevelResource::TILE_SIZE,
-            LevelResource::TILE_COLS,
-            LevelResource::TILE_ROWS,
-            None,
-            None,
-        ));
+        let texture_handle = asset_server.load(TileAtlas::TEXTURE_PATH);
+        let layout_handle = asset_server.add(TileAtlas::layout());
 
         let id = level_init.read().last().unwrap().0;
         command.insert_resource(LevelResource {
@@ -27,14 +22,15 @@ impl DataManager {
             texture_handle,
             layout_handle: layout_handle.clone(),
             data_handle: asset_server.load(LevelResource::data_path(id)),
+            tiles_handle: asset_server.load(TileRegistry::ASSET_PATH),
         });
         command.insert_resource(PlayerResource::new(&asset_server));
+        command.insert_resource(EnemyResource::new(&asset_server));
     }
 }
 impl Plugin for DataManager {
     fn build(&self, app: &mut App) {
-        app.init_asset::<level::LevelAsset>()
-            .init_asset_loader::<level::LevelAssetLoader>()
+        app.add_plugins(LevelFormatPlugin)
             .add_event::<LevelInit>()
             .add_event::<LevelPass>()
             .add_systems(OnEnter(GameScene::InGame), Self::load_running_resource);
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use sara_level::prelude::MovementProfile;
 use std::collections::HashMap;
 use strum::{EnumIter, IntoEnumIterator};
 
@@ -8,6 +9,7 @@ pub enum PlayerAsepriteType {
     Walk,
     Idle,
     Fall,
+    Beat,
 }
 impl PlayerAsepriteType {
     pub const fn name(&self) -> &'static str {
@@ -16,6 +18,7 @@ impl PlayerAsepriteType {
             Self::Walk => "walk",
             Self::Idle => "idle",
             Self::Fall => "fall",
+            Self::Beat => "beat",
         }
     }
 
@@ -25,6 +28,7 @@ impl PlayerAsepriteType {
             Self::Walk => 3,
             Self::Idle => 1,
             Self::Fall => 1,
+            Self::Beat => 2,
         }
     }
 
@@ -45,6 +49,10 @@ impl PlayerAsepriteType {
             Self::Fall => {
                 TextureAtlasLayout::from_grid(PlayerResource::TEXTURE_SIZE, 1, 1, None, None)
             }
+
+            Self::Beat => {
+                TextureAtlasLayout::from_grid(PlayerResource::TEXTURE_SIZE, 2, 1, None, None)
+            }
         }
     }
 }
@@ -53,6 +61,7 @@ impl PlayerAsepriteType {
 pub struct PlayerResource {
     pub texture_atlas_handles:
         HashMap<PlayerAsepriteType, (Handle<Image>, Handle<TextureAtlasLayout>)>,
+    pub movement_handle: Handle<MovementProfile>,
 }
 impl PlayerResource {
     pub const TEXTURE_SIZE: UVec2 = UVec2::new(48, 48);
@@ -77,6 +86,7 @@ impl PlayerResource {
         }
         Self {
             texture_atlas_handles,
+            movement_handle: asset_server.load(MovementProfile::ASSET_PATH),
         }
     }
 }
@@ -1,2 +1,3 @@
 pub use super::level::*;
-pub use super::player::*;
\ No newline at end of file
+pub use super::player::*;
+pub use super::enemy::*;
\ No newline at end of file
@@ -2,6 +2,8 @@ mod data;
 mod model;
 mod scene;
 mod sound;
+#[cfg(test)]
+mod testing;
 mod utils;
 use avian2d::prelude::*;
 use bevy::prelude::*;
@@ -9,7 +11,9 @@ use bevy::prelude::*;
 fn main() {
     App::new()
         .add_plugins(scene::ScenePlugins)
-        .add_plugins(PhysicsPlugins::default())
+        .add_plugins(
+            PhysicsPlugins::default().with_collision_hooks::<model::prelude::TileCollisionHooks>(),
+        )
         .add_plugins(utils::aseprite::AsepritePlugin)
         .add_plugins(data::DataManager)
         .add_plugins(model::ModelManager)
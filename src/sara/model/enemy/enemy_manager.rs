@@ -0,0 +1,260 @@
+use super::super::player::prelude::*;
+use super::*;
+
+type WolfQuery<'a, 'b, 'c> = Query<
+    'a,
+    'b,
+    (
+        &'c mut Wolf,
+        &'c EnemySpawnPoint,
+        &'c Transform,
+        &'c mut LinearVelocity,
+        &'c mut Sprite,
+        &'c mut AsepriteIndices,
+    ),
+    Without<EnemyWait>,
+>;
+
+type BatQuery<'a, 'b, 'c> = Query<
+    'a,
+    'b,
+    (
+        &'c mut Bat,
+        &'c EnemySpawnPoint,
+        &'c Transform,
+        &'c mut LinearVelocity,
+        &'c mut Sprite,
+        &'c mut AsepriteIndices,
+    ),
+    Without<EnemyWait>,
+>;
+
+pub struct EnemyManager;
+impl EnemyManager {
+    const CHARGE_FACTOR: f32 = 3.0;
+    const CHARGE_TIME: f32 = 1.2;
+    const REST_TIME: f32 = 0.8;
+    const WALL_DISTANCE: f32 = Enemy::WOLF_COLLIDER_SIZE.0 / 2.0 + 2.0;
+    const LEDGE_DISTANCE: f32 = Enemy::WOLF_COLLIDER_SIZE.1 / 2.0 + 4.0;
+    const DIVE_FACTOR: f32 = 3.0;
+    const ARRIVED_DISTANCE: f32 = 4.0;
+    // how fast a bat pulls back onto its flight when the physics step drifted
+    const PATH_CORRECTION: f32 = 8.0;
+
+    fn init(
+        mut command: Commands,
+        level_resource: Res<LevelResource>,
+        level_asset: Res<Assets<LevelAsset>>,
+        enemy_resource: Res<EnemyResource>,
+    ) {
+        let level_data = level_asset.get(&level_resource.data_handle).unwrap();
+        for spawn in &level_data.enemies {
+            if !spawn.is_valid() {
+                continue;
+            }
+            let mut entity = command.spawn((
+                Enemy::new(spawn, &enemy_resource),
+                StateScoped(PlayerState::Running),
+            ));
+            match spawn.kind {
+                EnemyKind::Wolf { .. } => entity.insert(Wolf::default()),
+                EnemyKind::Bat { .. } => entity.insert(Bat::default()),
+            };
+            entity
+                .with_child(EnemyHitBox::new(&spawn.kind))
+                .observe(Self::pause)
+                .observe(Self::hurt);
+        }
+    }
+
+    fn pause(
+        trigger: Trigger<EnemyWaitChange>,
+        mut command: Commands,
+        mut enemies: Query<(&mut AsepritePlaying, Has<EnemyWait>)>,
+    ) {
+        let Ok((mut playing, waiting)) = enemies.get_mut(trigger.target()) else {
+            return;
+        };
+        if waiting {
+            command.entity(trigger.target()).remove::<EnemyWait>();
+        } else {
+            command.entity(trigger.target()).insert(EnemyWait);
+        }
+        playing.0 = waiting;
+    }
+
+    fn hurt(
+        trigger: Trigger<PlayerAttackHit>,
+        mut command: Commands,
+        mut enemies: Query<&mut EnemyHP>,
+    ) {
+        let Ok(mut hp) = enemies.get_mut(trigger.target()) else {
+            return;
+        };
+        hp.0 = hp.0.saturating_sub(1);
+        if hp.0 == 0 {
+            command.entity(trigger.target()).despawn();
+        }
+    }
+
+    // whatever falls out of the level is gone for good
+    fn fall_out(
+        mut command: Commands,
+        level_resource: Res<LevelResource>,
+        level_asset: Res<Assets<LevelAsset>>,
+        enemies: Query<(Entity, &Transform), With<EnemyMarker>>,
+    ) {
+        let Some(level_data) = level_asset.get(&level_resource.data_handle) else {
+            return;
+        };
+        for (entity, transform) in enemies {
+            if transform.translation.y < level_data.kill_plane() {
+                command.entity(entity).despawn();
+            }
+        }
+    }
+
+    // a wall ahead or no ground ahead turns a wolf around
+    fn is_blocked(spatial_query: &SpatialQuery, position: Vec2, direction: f32) -> bool {
+        let filter = SpatialQueryFilter::default().with_mask([
+            GameCollisionLayers::Enviroment,
+            GameCollisionLayers::Platform,
+        ]);
+        let forward = Dir2::new(Vec2::new(direction, 0.0)).unwrap_or(Dir2::X);
+        let wall = spatial_query
+            .cast_ray(position, forward, Self::WALL_DISTANCE, true, &filter)
+            .is_some();
+        let ledge = spatial_query
+            .cast_ray(
+                position + Vec2::new(direction * Self::WALL_DISTANCE, 0.0),
+                Dir2::NEG_Y,
+                Self::LEDGE_DISTANCE,
+                true,
+                &filter,
+            )
+            .is_none();
+        wall || ledge
+    }
+
+    fn wolf(
+        time: Res<Time>,
+        spatial_query: SpatialQuery,
+        player: PlayerTransformQuery,
+        wolves: WolfQuery,
+    ) {
+        let delta = time.delta_secs();
+        for (mut wolf, spawn, transform, mut velocity, mut sprite, mut indices) in wolves {
+            let EnemyKind::Wolf {
+                patrol,
+                speed,
+                sight,
+            } = spawn.0.kind
+            else {
+                continue;
+            };
+            let position = transform.translation.truncate();
+            let to_player = player.translation.truncate() - position;
+            let blocked = Self::is_blocked(&spatial_query, position, wolf.direction);
+            let next = match wolf.state {
+                WolfState::Patrol => {
+                    let sees = to_player.x.abs() <= sight
+                        && to_player.y.abs() <= Enemy::WOLF_SIZE.1
+                        && to_player.x * wolf.direction > 0.0;
+                    if sees && !blocked {
+                        WolfState::Charge(Self::CHARGE_TIME)
+                    } else {
+                        // past the patrol range it walks back to its spawn
+                        if blocked || (position.x - spawn.0.position.0) * wolf.direction > patrol {
+                            wolf.direction = -wolf.direction;
+                        }
+                        WolfState::Patrol
+                    }
+                }
+                WolfState::Charge(left) if left > delta && !blocked => {
+                    WolfState::Charge(left - delta)
+                }
+                WolfState::Charge(_) => WolfState::Rest(Self::REST_TIME),
+                WolfState::Rest(left) if left > delta => WolfState::Rest(left - delta),
+                WolfState::Rest(_) => WolfState::Patrol,
+            };
+            if next.frames() != wolf.state.frames() {
+                Enemy::play(&mut sprite, &mut indices, next.frames());
+            }
+            wolf.state = next;
+            velocity.x = match wolf.state {
+                WolfState::Patrol => wolf.direction * speed,
+                WolfState::Charge(_) => wolf.direction * speed * Self::CHARGE_FACTOR,
+                WolfState::Rest(_) => 0.0,
+            };
+            sprite.flip_x = wolf.direction < 0.0;
+        }
+    }
+
+    fn bat(time: Res<Time>, player: PlayerTransformQuery, bats: BatQuery) {
+        for (mut bat, spawn, transform, mut velocity, mut sprite, mut indices) in bats {
+            let EnemyKind::Bat {
+                pattern,
+                range,
+                speed,
+            } = spawn.0.kind
+            else {
+                continue;
+            };
+            bat.clock += time.delta_secs();
+            let position = transform.translation.truncate();
+            let home = Vec2::from(spawn.0.position);
+            let next = match (pattern, bat.state) {
+                (FlightPattern::Sine, _) => BatState::Fly,
+                (FlightPattern::Dive, BatState::Fly) => {
+                    let to_player = player.translation.truncate() - position;
+                    if to_player.y < 0.0 && to_player.length() <= range * 2.0 {
+                        BatState::Dive(player.translation.truncate())
+                    } else {
+                        BatState::Fly
+                    }
+                }
+                (FlightPattern::Dive, BatState::Dive(target))
+                    if position.distance(target) > Self::ARRIVED_DISTANCE =>
+                {
+                    BatState::Dive(target)
+                }
+                (FlightPattern::Dive, BatState::Dive(_)) => BatState::Return,
+                (FlightPattern::Dive, BatState::Return)
+                    if position.distance(home) > Self::ARRIVED_DISTANCE =>
+                {
+                    BatState::Return
+                }
+                (FlightPattern::Dive, BatState::Return) => BatState::Fly,
+            };
+            if next.frames() != bat.state.frames() {
+                Enemy::play(&mut sprite, &mut indices, next.frames());
+            }
+            bat.state = next;
+            velocity.0 = match bat.state {
+                BatState::Fly => {
+                    let target = spawn.0.flight_position_at(bat.clock);
+                    spawn.0.flight_velocity_at(bat.clock)
+                        + (target - position) * Self::PATH_CORRECTION
+                }
+                BatState::Dive(target) => {
+                    (target - position).normalize_or_zero() * speed * Self::DIVE_FACTOR
+                }
+                BatState::Return => (home - position).normalize_or_zero() * speed,
+            };
+            if velocity.x != 0.0 {
+                sprite.flip_x = velocity.x > 0.0;
+            }
+        }
+    }
+}
+impl Plugin for EnemyManager {
+    fn build(&self, app: &mut App) {
+        app.add_event::<EnemyWaitChange>()
+            .add_systems(OnEnter(PlayerState::Loading), Self::init)
+            // steered once per physics step, the same moves at any frame rate
+            .add_systems(
+                FixedUpdate,
+                (Self::wolf, Self::bat, Self::fall_out).run_if(in_state(PlayerState::Running)),
+            );
+    }
+}
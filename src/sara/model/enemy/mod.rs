@@ -0,0 +1,226 @@
+pub mod enemy_manager;
+pub mod prelude;
+use super::GameCollisionLayers;
+use super::tile::prelude::{HitBoxMarker, HitDamage};
+use crate::data::prelude::*;
+use crate::utils::prelude::*;
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+#[derive(Event)]
+pub struct EnemyWaitChange;
+
+#[derive(Component)]
+pub struct EnemyMarker;
+
+// set while the level is paused, enemies keep their state until it is gone
+#[derive(Component)]
+struct EnemyWait;
+
+#[derive(Component)]
+struct EnemySpawnPoint(EnemySpawn);
+
+// attacks taken before the enemy is gone
+#[derive(Component)]
+struct EnemyHP(usize);
+impl EnemyHP {
+    const WOLF_HP: usize = 3;
+    const BAT_HP: usize = 1;
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum WolfState {
+    Patrol,
+    // seconds left
+    Charge(f32),
+    Rest(f32),
+}
+impl WolfState {
+    const fn frames(&self) -> (usize, usize) {
+        match self {
+            Self::Patrol => (0, 4),
+            Self::Charge(_) => (5, 9),
+            Self::Rest(_) => (10, 14),
+        }
+    }
+}
+
+#[derive(Component)]
+struct Wolf {
+    state: WolfState,
+    direction: f32,
+}
+impl Default for Wolf {
+    fn default() -> Self {
+        Self {
+            state: WolfState::Patrol,
+            direction: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum BatState {
+    Fly,
+    // where the player was when the dive started
+    Dive(Vec2),
+    Return,
+}
+impl BatState {
+    const fn frames(&self) -> (usize, usize) {
+        match self {
+            Self::Fly | Self::Return => (0, 3),
+            Self::Dive(_) => (5, 9),
+        }
+    }
+}
+
+#[derive(Component)]
+struct Bat {
+    state: BatState,
+    clock: f32,
+}
+impl Default for Bat {
+    fn default() -> Self {
+        Self {
+            state: BatState::Fly,
+            clock: 0.0,
+        }
+    }
+}
+
+#[derive(Bundle)]
+pub struct Enemy(
+    Aseprite,
+    Transform,
+    RigidBody,
+    Collider,
+    LockedAxes,
+    Friction,
+    Restitution,
+    LinearVelocity,
+    GravityScale,
+    CollisionLayers,
+    EnemySpawnPoint,
+    EnemyHP,
+    EnemyMarker,
+);
+impl Enemy {
+    const WOLF_SIZE: (f32, f32) = (64.0, 32.0);
+    const WOLF_COLLIDER_SIZE: (f32, f32) = (36.0, 20.0);
+    const BAT_SIZE: (f32, f32) = (34.0, 34.0);
+    const BAT_COLLIDER_RADIUS: f32 = 8.0;
+    const FRAME_TIME: f32 = 0.12;
+    const Z: f32 = 1.5;
+
+    fn play(sprite: &mut Sprite, indices: &mut AsepriteIndices, (first, last): (usize, usize)) {
+        *indices = AsepriteIndices::new(first, last);
+        if let Some(atlas) = &mut sprite.texture_atlas {
+            atlas.index = first;
+        }
+    }
+
+    fn aseprite(
+        resource: &EnemyResource,
+        aseprite_type: EnemyAsepriteType,
+        size: Vec2,
+        (first, last): (usize, usize),
+    ) -> Aseprite {
+        let (image, layout) = resource.texture_atlas_handles.get(&aseprite_type).unwrap();
+        Aseprite::new(
+            Sprite::from_atlas_image(
+                image.clone(),
+                TextureAtlas {
+                    layout: layout.clone(),
+                    index: first,
+                },
+            ),
+            AsepriteIndices::new(first, last),
+            AsepritePlaying(true),
+            AsepriteTimer(Timer::from_seconds(Self::FRAME_TIME, TimerMode::Repeating)),
+        )
+        .with_size(size)
+    }
+
+    // wolves walk on the environment, bats fly through it
+    fn new(spawn: &EnemySpawn, resource: &EnemyResource) -> Self {
+        let translation = Vec2::from(spawn.position).extend(Self::Z);
+        let (aseprite, body, collider, gravity, filters, hp) = match spawn.kind {
+            EnemyKind::Wolf { .. } => (
+                Self::aseprite(
+                    resource,
+                    EnemyAsepriteType::Wolf,
+                    Vec2::new(Self::WOLF_SIZE.0, Self::WOLF_SIZE.1),
+                    WolfState::Patrol.frames(),
+                ),
+                RigidBody::Dynamic,
+                Collider::rectangle(Self::WOLF_COLLIDER_SIZE.0, Self::WOLF_COLLIDER_SIZE.1),
+                1.0,
+                LayerMask::from([
+                    GameCollisionLayers::Enviroment,
+                    GameCollisionLayers::Platform,
+                ]),
+                EnemyHP::WOLF_HP,
+            ),
+            EnemyKind::Bat { .. } => (
+                Self::aseprite(
+                    resource,
+                    EnemyAsepriteType::Bat,
+                    Vec2::new(Self::BAT_SIZE.0, Self::BAT_SIZE.1),
+                    BatState::Fly.frames(),
+                ),
+                RigidBody::Kinematic,
+                Collider::circle(Self::BAT_COLLIDER_RADIUS),
+                0.0,
+                LayerMask::NONE,
+                EnemyHP::BAT_HP,
+            ),
+        };
+        Self(
+            aseprite,
+            Transform::from_translation(translation),
+            body,
+            collider,
+            LockedAxes::ROTATION_LOCKED,
+            Friction::ZERO.with_combine_rule(CoefficientCombine::Min),
+            Restitution::ZERO.with_combine_rule(CoefficientCombine::Min),
+            LinearVelocity::ZERO,
+            GravityScale(gravity),
+            CollisionLayers::new(GameCollisionLayers::Enemy, filters),
+            EnemySpawnPoint(spawn.clone()),
+            EnemyHP(hp),
+            EnemyMarker,
+        )
+    }
+}
+
+// what hurts the player, a sensor on the hit layer like damaging tiles
+#[derive(Bundle)]
+struct EnemyHitBox(Collider, Sensor, CollisionLayers, HitBoxMarker, HitDamage);
+impl EnemyHitBox {
+    const WOLF_DAMAGE: usize = 2;
+    const BAT_DAMAGE: usize = 1;
+
+    fn new(kind: &EnemyKind) -> Self {
+        let (collider, damage) = match kind {
+            EnemyKind::Wolf { .. } => (
+                Collider::rectangle(
+                    Enemy::WOLF_COLLIDER_SIZE.0 - 4.0,
+                    Enemy::WOLF_COLLIDER_SIZE.1 - 4.0,
+                ),
+                Self::WOLF_DAMAGE,
+            ),
+            EnemyKind::Bat { .. } => (
+                Collider::circle(Enemy::BAT_COLLIDER_RADIUS),
+                Self::BAT_DAMAGE,
+            ),
+        };
+        Self(
+            collider,
+            Sensor,
+            CollisionLayers::new(GameCollisionLayers::Hit, GameCollisionLayers::Player),
+            HitBoxMarker,
+            HitDamage(damage),
+        )
+    }
+}
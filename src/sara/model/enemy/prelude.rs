@@ -0,0 +1,2 @@
+pub use super::*;
+pub use enemy_manager::*;
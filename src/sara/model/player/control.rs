@@ -0,0 +1,370 @@
+use crate::data::prelude::MovementProfile;
+use bevy::prelude::*;
+
+// how forgiving a jump is, both in seconds
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct JumpWindows {
+    // a jump still counts this long after the ground is gone
+    pub coyote_time: f32,
+    // a press is kept this long waiting for the ground
+    pub buffer_time: f32,
+}
+impl JumpWindows {
+    pub const DEFAULT_COYOTE_TIME: f32 = 0.1;
+    pub const DEFAULT_BUFFER_TIME: f32 = 0.12;
+}
+impl Default for JumpWindows {
+    fn default() -> Self {
+        Self {
+            coyote_time: Self::DEFAULT_COYOTE_TIME,
+            buffer_time: Self::DEFAULT_BUFFER_TIME,
+        }
+    }
+}
+
+// a button read by fixed steps: a frame can run no step or several, so a press is kept
+// until one step has seen it and then dropped, it is never lost nor seen twice
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LatchedButton {
+    held: bool,
+    pressed: bool,
+}
+impl LatchedButton {
+    // called once a frame
+    pub fn sample(&mut self, held: bool, just_pressed: bool) {
+        self.held = held;
+        self.pressed |= just_pressed;
+    }
+
+    pub fn held(&self) -> bool {
+        self.held
+    }
+
+    pub fn just_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    // called once at the end of every fixed step
+    pub fn consume(&mut self) {
+        self.pressed = false;
+    }
+}
+
+// seconds since the player last stood on ground and since jump was last pressed
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct JumpAssist {
+    since_ground: Option<f32>,
+    since_press: Option<f32>,
+}
+impl JumpAssist {
+    // called once a step, true when the player should jump now
+    pub fn update(
+        &mut self,
+        windows: &JumpWindows,
+        delta: f32,
+        grounded: bool,
+        pressed: bool,
+    ) -> bool {
+        let age = |since: Option<f32>, window: f32| {
+            since
+                .map(|since| since + delta)
+                .filter(|since| *since <= window)
+        };
+        self.since_ground = if grounded {
+            Some(0.0)
+        } else {
+            age(self.since_ground, windows.coyote_time)
+        };
+        self.since_press = if pressed {
+            Some(0.0)
+        } else {
+            age(self.since_press, windows.buffer_time)
+        };
+        let jump = self.since_ground.is_some() && self.since_press.is_some();
+        // a jump uses up both, one press never jumps twice
+        if jump {
+            self.reset();
+        }
+        jump
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+// the player's own horizontal speed and whether it is fast enough to count as walking
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RunMotion {
+    speed: f32,
+    walking: bool,
+}
+impl RunMotion {
+    // the horizontal speed after `delta` seconds of steering toward `target`
+    fn steer(
+        profile: &MovementProfile,
+        speed: f32,
+        target: f32,
+        grounded: bool,
+        delta: f32,
+    ) -> f32 {
+        let rate = if speed * target < 0.0 {
+            profile.turn_deceleration
+        } else if target.abs() > speed.abs() {
+            profile.acceleration
+        } else {
+            // nothing held, or faster than the walk speed after a dash or a wall jump
+            profile.deceleration
+        };
+        let control = if grounded { 1.0 } else { profile.air_control };
+        let step = rate * control * delta;
+        // lands on the target exactly, a rounding error would never count as standing
+        if (target - speed).abs() <= step {
+            target
+        } else {
+            speed + step.copysign(target - speed)
+        }
+    }
+
+    // called once a step with the held direction, -1, 0 or 1
+    pub fn update(
+        &mut self,
+        profile: &MovementProfile,
+        direction: f32,
+        grounded: bool,
+        delta: f32,
+    ) -> f32 {
+        self.speed = Self::steer(
+            profile,
+            self.speed,
+            direction * profile.walk_speed,
+            grounded,
+            delta,
+        );
+        let speed = self.speed.abs();
+        self.walking = if self.walking {
+            speed > profile.walk_stop_speed
+        } else {
+            speed >= profile.walk_start_speed
+        };
+        self.speed
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn is_walking(&self) -> bool {
+        self.walking
+    }
+
+    // picks up a speed the player did not steer into, a dash, a knockback or a push off a wall
+    pub fn carry(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    // a wall in the way, nothing is kept
+    pub fn stop(&mut self) {
+        self.speed = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: f32 = 1.0 / 64.0;
+    const PROFILE: MovementProfile = MovementProfile::DEFAULT;
+    // only a press on the ground jumps
+    const NO_WINDOWS: JumpWindows = JumpWindows {
+        coyote_time: 0.0,
+        buffer_time: 0.0,
+    };
+
+    // one (grounded, pressed) pair per step, returns the steps a jump started on
+    fn jumps(windows: JumpWindows, steps: &[(bool, bool)]) -> Vec<usize> {
+        let mut assist = JumpAssist::default();
+        steps
+            .iter()
+            .enumerate()
+            .filter(|(_, (grounded, pressed))| assist.update(&windows, STEP, *grounded, *pressed))
+            .map(|(step, _)| step)
+            .collect()
+    }
+
+    fn steps_for(seconds: f32) -> usize {
+        (seconds / STEP).floor() as usize
+    }
+
+    // one held direction per step, the run motion after each of them
+    fn run(motion: &mut RunMotion, directions: &[f32]) -> Vec<RunMotion> {
+        directions
+            .iter()
+            .map(|direction| {
+                motion.update(&PROFILE, *direction, true, STEP);
+                *motion
+            })
+            .collect()
+    }
+
+    #[test]
+    fn a_press_is_kept_until_a_step_consumes_it() {
+        let mut button = LatchedButton::default();
+        button.sample(true, true);
+        // a frame with no fixed step in it
+        button.sample(true, false);
+        assert!(button.just_pressed());
+        button.consume();
+        assert!(!button.just_pressed());
+        assert!(button.held());
+    }
+
+    #[test]
+    fn a_tap_within_one_frame_still_counts() {
+        let mut button = LatchedButton::default();
+        button.sample(false, true);
+        assert!(button.just_pressed());
+        assert!(!button.held());
+    }
+
+    #[test]
+    fn a_press_on_the_ground_jumps_right_away() {
+        assert_eq!(
+            jumps(JumpWindows::default(), &[(true, false), (true, true)]),
+            [1]
+        );
+    }
+
+    #[test]
+    fn a_press_just_before_landing_is_buffered() {
+        let steps = [(false, true), (false, false), (false, false), (true, false)];
+        assert_eq!(jumps(JumpWindows::default(), &steps), [3]);
+    }
+
+    #[test]
+    fn a_press_long_before_landing_is_dropped() {
+        let windows = JumpWindows::default();
+        let mut steps = vec![(false, true)];
+        steps.extend(std::iter::repeat_n(
+            (false, false),
+            steps_for(windows.buffer_time) + 1,
+        ));
+        steps.push((true, false));
+        assert!(jumps(windows, &steps).is_empty());
+    }
+
+    #[test]
+    fn a_press_just_after_leaving_a_ledge_still_jumps() {
+        let steps = [(true, false), (false, false), (false, false), (false, true)];
+        assert_eq!(jumps(JumpWindows::default(), &steps), [3]);
+    }
+
+    #[test]
+    fn a_press_long_after_leaving_a_ledge_is_ignored() {
+        let windows = JumpWindows::default();
+        let mut steps = vec![(true, false)];
+        steps.extend(std::iter::repeat_n(
+            (false, false),
+            steps_for(windows.coyote_time) + 1,
+        ));
+        steps.push((false, true));
+        assert!(jumps(windows, &steps).is_empty());
+    }
+
+    #[test]
+    fn one_press_jumps_once() {
+        // the ground is still under the player for a step after the jump started
+        let steps = [(true, true), (true, false), (false, false), (true, false)];
+        assert_eq!(jumps(JumpWindows::default(), &steps), [0]);
+    }
+
+    #[test]
+    fn a_coyote_jump_uses_up_the_ledge() {
+        let steps = [(true, false), (false, true), (false, false), (false, true)];
+        assert_eq!(jumps(JumpWindows::default(), &steps), [1]);
+    }
+
+    #[test]
+    fn no_windows_only_jump_from_the_ground() {
+        let steps = [(false, true), (true, false), (true, true), (false, true)];
+        assert_eq!(jumps(NO_WINDOWS, &steps), [2]);
+    }
+
+    #[test]
+    fn reset_forgets_a_pending_press() {
+        let windows = JumpWindows::default();
+        let mut assist = JumpAssist::default();
+        assert!(!assist.update(&windows, STEP, false, true));
+        assist.reset();
+        assert!(!assist.update(&windows, STEP, true, false));
+    }
+
+    #[test]
+    fn the_air_steers_slower_than_the_ground() {
+        let ground = RunMotion::steer(&PROFILE, 0.0, PROFILE.walk_speed, true, STEP);
+        let air = RunMotion::steer(&PROFILE, 0.0, PROFILE.walk_speed, false, STEP);
+        assert!(air < ground);
+        assert!((air - ground * PROFILE.air_control).abs() < 1e-4);
+    }
+
+    #[test]
+    fn turning_around_brakes_harder_than_letting_go() {
+        let released = RunMotion::steer(&PROFILE, PROFILE.walk_speed, 0.0, true, STEP);
+        let turned = RunMotion::steer(
+            &PROFILE,
+            PROFILE.walk_speed,
+            -PROFILE.walk_speed,
+            true,
+            STEP,
+        );
+        assert!(turned < released);
+        assert_eq!(
+            turned,
+            PROFILE.walk_speed - PROFILE.turn_deceleration * STEP
+        );
+    }
+
+    #[test]
+    fn faster_than_walking_slows_down_by_friction() {
+        let dash = PROFILE.ground_dash_speed;
+        assert_eq!(
+            RunMotion::steer(&PROFILE, dash, PROFILE.walk_speed, true, STEP),
+            dash - PROFILE.deceleration * STEP
+        );
+    }
+
+    #[test]
+    fn holding_a_direction_ramps_up_to_the_walk_speed() {
+        let steps = run(&mut RunMotion::default(), &[1.0; 10]);
+        let speeds = steps.iter().map(RunMotion::speed).collect::<Vec<_>>();
+        assert!((speeds[0] - PROFILE.acceleration * STEP).abs() < 1e-3);
+        assert!(speeds.is_sorted());
+        assert_eq!(speeds[9], PROFILE.walk_speed);
+        // and stays there
+        assert_eq!(run(&mut steps[9].clone(), &[1.0])[0], steps[9]);
+    }
+
+    #[test]
+    fn walking_starts_and_stops_at_the_speed_thresholds() {
+        let mut motion = RunMotion::default();
+        for step in run(&mut motion, &[1.0; 10]) {
+            assert_eq!(step.is_walking(), step.speed() >= PROFILE.walk_start_speed);
+        }
+        for step in run(&mut motion, &[0.0; 10]) {
+            assert_eq!(step.is_walking(), step.speed() > PROFILE.walk_stop_speed);
+        }
+        let standing = run(&mut motion, &[0.0]);
+        assert_eq!(standing[0].speed(), 0.0);
+        assert!(!standing[0].is_walking());
+    }
+
+    #[test]
+    fn turning_around_passes_through_standing() {
+        let mut motion = RunMotion::default();
+        run(&mut motion, &[1.0; 10]);
+        let steps = run(&mut motion, &[-1.0; 10]);
+        let turned = steps.iter().position(|step| step.speed() <= 0.0).unwrap();
+        assert!(steps[..=turned].iter().any(|step| !step.is_walking()));
+        assert_eq!(steps.last().unwrap().speed(), -PROFILE.walk_speed);
+    }
+}
@@ -1,11 +1,19 @@
+pub mod control;
 pub mod player_manager;
 pub mod prelude;
 use super::GameCollisionLayers;
+use super::tile::prelude::{MovingPlatformMarker, PassThroughPlatform};
 use crate::utils::prelude::*;
 use crate::{data::prelude::*, scene::GameScene};
 use avian2d::prelude::*;
+use bevy::ecs::entity::hash_set::EntityHashSet;
 use bevy::ecs::query::QueryData;
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
+use bevy_transform_interpolation::TranslationEasingState;
+use control::*;
+use std::time::Duration;
 
 #[derive(SubStates, Clone, Copy, Default, Debug, Hash, PartialEq, Eq)]
 #[source(GameScene = GameScene::InGame)]
@@ -20,36 +28,342 @@ pub enum PlayerState {
 #[derive(Event)]
 pub struct PlayerWaitChange;
 
+// triggered on whatever the attack hitbox touches, enemies and breakable tiles observe it
+#[derive(Event)]
+pub struct PlayerAttackHit;
+
+// triggered on the player when its hp runs out or it falls out of the level
+#[derive(Event)]
+pub struct PlayerDied;
+
+// triggered on the player by a pickup, the ability stays unlocked and is saved
+#[derive(Event)]
+pub struct PlayerUnlock(pub Ability);
+
+// lives left in this run and where the player comes back after losing one,
+// losing the last one ends the run
+#[derive(Resource)]
+pub struct PlayerLives {
+    pub lives: usize,
+    pub respawn: Vec2,
+}
+impl PlayerLives {
+    pub const MAX_LIVES: usize = 3;
+}
+impl Default for PlayerLives {
+    fn default() -> Self {
+        Self {
+            lives: Self::MAX_LIVES,
+            respawn: Vec2::ZERO,
+        }
+    }
+}
+
+// the movement profile once it is loaded, the built-in one until then
+#[derive(SystemParam)]
+pub struct Movement<'w> {
+    player_resource: Res<'w, PlayerResource>,
+    profiles: Res<'w, Assets<MovementProfile>>,
+}
+impl Movement<'_> {
+    pub fn profile(&self) -> &MovementProfile {
+        self.profiles
+            .get(&self.player_resource.movement_handle)
+            .unwrap_or(&MovementProfile::DEFAULT)
+    }
+}
+
+// the keys the controller reads, sampled every frame for the fixed steps
+#[derive(Resource, Default)]
+struct PlayerInput {
+    left: LatchedButton,
+    right: LatchedButton,
+    down: LatchedButton,
+    jump: LatchedButton,
+    attack: LatchedButton,
+    dash: LatchedButton,
+}
+impl PlayerInput {
+    fn sample(&mut self, input: &ButtonInput<KeyCode>) {
+        for (button, keys) in [
+            (
+                &mut self.left,
+                [KeyCode::KeyA, KeyCode::ArrowLeft].as_slice(),
+            ),
+            (&mut self.right, &[KeyCode::KeyD, KeyCode::ArrowRight]),
+            (&mut self.down, &[KeyCode::KeyS, KeyCode::ArrowDown]),
+            (&mut self.jump, &[KeyCode::Space]),
+            (&mut self.attack, &[KeyCode::KeyJ]),
+            (&mut self.dash, &[KeyCode::KeyK]),
+        ] {
+            button.sample(
+                input.any_pressed(keys.iter().copied()),
+                input.any_just_pressed(keys.iter().copied()),
+            );
+        }
+    }
+
+    fn consume(&mut self) {
+        for button in [
+            &mut self.left,
+            &mut self.right,
+            &mut self.down,
+            &mut self.jump,
+            &mut self.attack,
+            &mut self.dash,
+        ] {
+            button.consume();
+        }
+    }
+
+    // -1 for left, 1 for right, 0 for neither or both
+    fn direction(&self) -> f32 {
+        (self.right.held() as i32 - self.left.held() as i32) as f32
+    }
+}
+
 #[derive(Component)]
 pub struct PlayerMarker;
 pub type PlayerLinearVelocityQueryMut<'a, 'b> =
     Single<'a, &'b mut LinearVelocity, With<PlayerMarker>>;
 pub type PlayerLinearVelocityQuery<'a, 'b> = Single<'a, &'b LinearVelocity, With<PlayerMarker>>;
 pub type PlayerTransformQuery<'a, 'b> = Single<'a, &'b Transform, With<PlayerMarker>>;
+type PlayerGravityScaleQuery<'a, 'b> = Single<'a, &'b mut GravityScale, With<PlayerMarker>>;
 
 #[derive(Component)]
-struct HP(usize);
+pub struct HP(pub usize);
 impl HP {
-    const MAX_HP: usize = 200;
+    pub const MAX_HP: usize = 200;
 }
 impl Default for HP {
     fn default() -> Self {
         Self(Self::MAX_HP)
     }
 }
-type PlayerHPQuery<'a, 'b> = Single<'a, &'b mut HP, With<PlayerMarker>>;
 
 #[derive(SubStates, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 #[source(PlayerState = PlayerState::Running)]
+#[states(scoped_entities)]
 enum PlayerRunningState {
     #[default]
     Fall,
     Jump,
     Walk,
     Idle,
+    Attack,
+    Hurt,
+    WallSlide,
+    DoubleJump,
+    AirDash,
+    GroundDash,
     Wait,
 }
 
+// applies PlayerRunningState alone, once every fixed step
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+struct PlayerStateTransition;
+
+#[derive(Component)]
+struct PlayerAttack {
+    timer: Timer,
+    cooldown: Timer,
+}
+impl PlayerAttack {
+    const ATTACK_TIME: f32 = 0.3;
+    const COOLDOWN_TIME: f32 = 0.45;
+}
+impl Default for PlayerAttack {
+    fn default() -> Self {
+        // the first attack needs no wait
+        let mut cooldown = Timer::from_seconds(Self::COOLDOWN_TIME, TimerMode::Once);
+        let duration = cooldown.duration();
+        cooldown.tick(duration);
+        Self {
+            timer: Timer::from_seconds(Self::ATTACK_TIME, TimerMode::Once),
+            cooldown,
+        }
+    }
+}
+type PlayerAttackQuery<'a, 'b> = Single<'a, &'b mut PlayerAttack, With<PlayerMarker>>;
+
+type PlayerRespawnQuery<'a, 'b> = Single<
+    'a,
+    (
+        &'b mut Transform,
+        &'b mut TranslationEasingState,
+        &'b mut LinearVelocity,
+        &'b mut HP,
+        &'b mut PlayerHurt,
+    ),
+    With<PlayerMarker>,
+>;
+
+#[derive(Component, Default)]
+struct PlayerJump(JumpAssist);
+
+// the player's own horizontal speed, a moving platform underneath adds to it
+#[derive(Component, Default)]
+struct PlayerRun(RunMotion);
+
+// abilities kept from one level to the next
+#[derive(Resource, Default)]
+pub struct UnlockedAbilities(pub AbilitySet);
+
+// what the player can do on this level, the run's abilities and the level's own
+#[derive(Component)]
+struct PlayerAbilities(AbilitySet);
+
+#[derive(Component, Default)]
+struct PlayerCooldowns(AbilityCooldowns);
+
+// moves the player gets once per time in the air, landing gives them back
+#[derive(Component, Default)]
+struct PlayerAirMoves {
+    jumped: bool,
+    dashed: bool,
+}
+
+type PlayerDoubleJumpQuery<'a, 'b> = Single<
+    'a,
+    (
+        &'b PlayerAbilities,
+        &'b mut PlayerCooldowns,
+        &'b mut PlayerAirMoves,
+        &'b mut PlayerJump,
+    ),
+    With<PlayerMarker>,
+>;
+
+// a dash holds its speed until the timer is done, input is ignored meanwhile
+#[derive(Component)]
+struct PlayerDash {
+    timer: Timer,
+    direction: f32,
+}
+impl PlayerDash {
+    const DASH_TIME: f32 = 0.15;
+}
+impl Default for PlayerDash {
+    fn default() -> Self {
+        Self {
+            timer: Timer::from_seconds(Self::DASH_TIME, TimerMode::Once),
+            direction: 1.0,
+        }
+    }
+}
+type PlayerDashQuery<'a, 'b> = Single<
+    'a,
+    (
+        &'b PlayerAbilities,
+        &'b mut PlayerCooldowns,
+        &'b mut PlayerAirMoves,
+        &'b mut PlayerDash,
+        &'b Sprite,
+    ),
+    With<PlayerMarker>,
+>;
+
+// horizontal input is ignored for a moment after a wall jump, or it would undo the push
+#[derive(Component)]
+struct PlayerWallJump {
+    lockout: Timer,
+}
+impl PlayerWallJump {
+    const LOCKOUT_TIME: f32 = 0.15;
+    const SPEED: Vec2 = Vec2::new(140.0, 230.0);
+}
+impl Default for PlayerWallJump {
+    fn default() -> Self {
+        let mut lockout = Timer::from_seconds(Self::LOCKOUT_TIME, TimerMode::Once);
+        lockout.tick(lockout.duration());
+        Self { lockout }
+    }
+}
+type PlayerWallJumpQuery<'a, 'b> = Single<
+    'a,
+    (
+        &'b PlayerAbilities,
+        &'b mut PlayerJump,
+        &'b mut PlayerWallJump,
+        &'b mut Sprite,
+    ),
+    With<PlayerMarker>,
+>;
+
+// after a hit the input is locked for a moment and nothing hurts for a while longer
+#[derive(Component)]
+struct PlayerHurt {
+    lockout: Timer,
+    invulnerable: Timer,
+}
+impl PlayerHurt {
+    const LOCKOUT_TIME: f32 = 0.3;
+    const INVULNERABLE_TIME: f32 = 1.2;
+    const BLINK_TIME: f32 = 0.1;
+    const BLINK_ALPHA: f32 = 0.25;
+    const KNOCKBACK: Vec2 = Vec2::new(140.0, 160.0);
+
+    fn hit(&mut self) {
+        self.lockout.reset();
+        self.invulnerable.reset();
+    }
+
+    fn tick(&mut self, delta: Duration) {
+        self.lockout.tick(delta);
+        self.invulnerable.tick(delta);
+    }
+
+    fn is_invulnerable(&self) -> bool {
+        !self.invulnerable.finished()
+    }
+
+    fn alpha(&self) -> f32 {
+        let blinks = (self.invulnerable.elapsed_secs() / Self::BLINK_TIME) as usize;
+        if self.is_invulnerable() && blinks.is_multiple_of(2) {
+            Self::BLINK_ALPHA
+        } else {
+            1.0
+        }
+    }
+}
+impl Default for PlayerHurt {
+    fn default() -> Self {
+        let mut lockout = Timer::from_seconds(Self::LOCKOUT_TIME, TimerMode::Once);
+        let mut invulnerable = Timer::from_seconds(Self::INVULNERABLE_TIME, TimerMode::Once);
+        lockout.tick(lockout.duration());
+        invulnerable.tick(invulnerable.duration());
+        Self {
+            lockout,
+            invulnerable,
+        }
+    }
+}
+
+// the shape is only used for spatial queries, it never becomes a physics collider
+#[derive(Component)]
+struct AttackHitBox {
+    shape: Collider,
+    timer: Timer,
+    hit: EntityHashSet,
+}
+impl AttackHitBox {
+    const SIZE: (f32, f32) = (20.0, 20.0);
+    const REACH: f32 = 14.0;
+    const ACTIVE_TIME: f32 = 0.15;
+
+    fn new() -> Self {
+        Self {
+            shape: Collider::rectangle(Self::SIZE.0, Self::SIZE.1),
+            timer: Timer::from_seconds(Self::ACTIVE_TIME, TimerMode::Once),
+            hit: EntityHashSet::default(),
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        !self.timer.finished()
+    }
+}
+
 #[derive(QueryData)]
 #[query_data(mutable)]
 pub struct PlayerAsepriteQueryData<'a> {
@@ -73,6 +387,18 @@ pub struct Player(
     Friction,
     Restitution,
     LinearVelocity,
+    GravityScale,
+    TransformInterpolation,
+    PassThroughPlatform,
+    PlayerAttack,
+    PlayerHurt,
+    PlayerJump,
+    PlayerRun,
+    PlayerAbilities,
+    PlayerWallJump,
+    PlayerCooldowns,
+    PlayerAirMoves,
+    PlayerDash,
     CollisionLayers,
     PlayerMarker,
 );
@@ -84,7 +410,7 @@ impl Player {
     const PLAYER_SIZE: (f32, f32) = (32.0, 32.0);
     const PLAYER_COLLIDER_SIZE: (f32, f32) = (10.0, 12.0);
 
-    fn new(transition: Vec3) -> Self {
+    fn new(transition: Vec3, abilities: AbilitySet) -> Self {
         Self(
             Aseprite::default()
                 .with_size(Vec2::new(Self::PLAYER_SIZE.0, Self::PLAYER_SIZE.1))
@@ -99,12 +425,26 @@ impl Player {
             Friction::ZERO.with_combine_rule(CoefficientCombine::Min),
             Restitution::ZERO.with_combine_rule(CoefficientCombine::Min),
             LinearVelocity(Vec2::new(0.0, 0.0)),
+            GravityScale(1.0),
+            // drawn between the fixed steps, or it would stutter whenever the frame rate is not the step rate
+            TransformInterpolation,
+            PassThroughPlatform::default(),
+            PlayerAttack::default(),
+            PlayerHurt::default(),
+            PlayerJump::default(),
+            PlayerRun::default(),
+            PlayerAbilities(abilities),
+            PlayerWallJump::default(),
+            PlayerCooldowns::default(),
+            PlayerAirMoves::default(),
+            PlayerDash::default(),
             CollisionLayers::new(
                 GameCollisionLayers::Player,
                 [
                     GameCollisionLayers::Hit,
                     GameCollisionLayers::Enviroment,
                     GameCollisionLayers::Operation,
+                    GameCollisionLayers::Platform,
                 ],
             ),
             PlayerMarker,
@@ -112,6 +452,21 @@ impl Player {
     }
 }
 
+// which profile distance a checker reaches, kept up to date when the profile reloads
+#[derive(Component, Clone, Copy)]
+enum CheckerReach {
+    Floor,
+    Wall,
+}
+impl CheckerReach {
+    fn distance(&self, profile: &MovementProfile) -> f32 {
+        match self {
+            Self::Floor => profile.floor_check_distance,
+            Self::Wall => profile.wall_check_distance,
+        }
+    }
+}
+
 #[derive(Component)]
 struct FloorChecker1;
 
@@ -139,26 +494,78 @@ struct BackChecker2;
 type BackWallQuery<'a, 'b, 'c> =
     Query<'a, 'b, &'c RayHits, Or<(With<BackChecker1>, With<BackChecker2>)>>;
 
+#[derive(Component)]
+struct SlopeChecker;
+
+type SlopeQuery<'a, 'b> = Single<'a, &'b RayHits, With<SlopeChecker>>;
+
+type PlatformVelocityQuery<'a, 'b, 'c> =
+    Query<'a, 'b, &'c LinearVelocity, (With<MovingPlatformMarker>, Without<PlayerMarker>)>;
+
+type GroundCheckerQuery<'a, 'b, 'c> = Query<
+    'a,
+    'b,
+    &'c mut RayCaster,
+    Or<(With<FloorChecker1>, With<FloorChecker2>, With<SlopeChecker>)>,
+>;
+
 struct PlayerCheckers;
 impl PlayerCheckers {
     const CHECKER_X: f32 = (Player::PLAYER_COLLIDER_SIZE.0 - 0.8) / 2.0;
     const FLOOR_CHECKER_Y: f32 = -(Player::PLAYER_SIZE.1 - 0.5) / 2.0;
-    const FLOOR_CHECKER_MAX_DISTANCE: f32 = 3.0;
     const WALL_CHECKER_Y: f32 = -(Player::PLAYER_SIZE.1 - 1.6) / 2.0;
-    const WALL_CHECKER_MAX_DISTANCE: f32 = 6.0;
+    const SLOPE_CHECKER_MAX_DISTANCE: f32 = Player::PLAYER_SIZE.1;
+    const MAX_SLOPE_ANGLE: f32 = 50.0;
+
+    // a hit from inside a collider has no normal, it never blocks
+    fn is_wall(normal: Vec2) -> bool {
+        normal != Vec2::ZERO && normal.y < Self::MAX_SLOPE_ANGLE.to_radians().cos()
+    }
+
+    fn is_blocked(hits: &RayHits) -> bool {
+        hits.iter().any(|hit| Self::is_wall(hit.normal))
+    }
+
+    // 1 for a wall on the right, -1 for one on the left
+    fn wall_side(
+        front_wall_query: &FrontWallQuery,
+        back_wall_query: &BackWallQuery,
+    ) -> Option<f32> {
+        if front_wall_query.iter().any(Self::is_blocked) {
+            Some(1.0)
+        } else if back_wall_query.iter().any(Self::is_blocked) {
+            Some(-1.0)
+        } else {
+            None
+        }
+    }
 
-    fn add_to(command: &mut ChildSpawnerCommands) {
+    // the walkable surface right under the player, flat or sloped
+    fn ground_normal(hits: &RayHits) -> Option<Vec2> {
+        hits.iter()
+            .map(|hit| hit.normal)
+            .find(|normal| *normal != Vec2::ZERO && !Self::is_wall(*normal))
+    }
+
+    // walls only come from the environment, one-way platforms are ground as well
+    fn ground_filter() -> SpatialQueryFilter {
+        SpatialQueryFilter::default().with_mask([
+            GameCollisionLayers::Enviroment,
+            GameCollisionLayers::Platform,
+        ])
+    }
+
+    fn add_to(command: &mut ChildSpawnerCommands, profile: &MovementProfile) {
         command.spawn((
             RayCaster::new(
                 Vec2::new(Self::CHECKER_X, Self::FLOOR_CHECKER_Y),
                 Dir2::NEG_Y,
             )
             .with_max_hits(1)
-            .with_max_distance(Self::FLOOR_CHECKER_MAX_DISTANCE)
-            .with_query_filter(
-                SpatialQueryFilter::default().with_mask(GameCollisionLayers::Enviroment),
-            ),
+            .with_max_distance(CheckerReach::Floor.distance(profile))
+            .with_query_filter(Self::ground_filter()),
             FloorChecker1,
+            CheckerReach::Floor,
         ));
         command.spawn((
             RayCaster::new(
@@ -166,29 +573,30 @@ impl PlayerCheckers {
                 Dir2::NEG_Y,
             )
             .with_max_hits(1)
-            .with_max_distance(Self::FLOOR_CHECKER_MAX_DISTANCE)
-            .with_query_filter(
-                SpatialQueryFilter::default().with_mask(GameCollisionLayers::Enviroment),
-            ),
+            .with_max_distance(CheckerReach::Floor.distance(profile))
+            .with_query_filter(Self::ground_filter()),
             FloorChecker2,
+            CheckerReach::Floor,
         ));
         command.spawn((
             RayCaster::new(Vec2::new(Self::CHECKER_X, Self::WALL_CHECKER_Y), Dir2::X)
                 .with_max_hits(1)
-                .with_max_distance(Self::WALL_CHECKER_MAX_DISTANCE)
+                .with_max_distance(CheckerReach::Wall.distance(profile))
                 .with_query_filter(
                     SpatialQueryFilter::default().with_mask(GameCollisionLayers::Enviroment),
                 ),
             FrontChecker1,
+            CheckerReach::Wall,
         ));
         command.spawn((
             RayCaster::new(Vec2::new(Self::CHECKER_X, -Self::WALL_CHECKER_Y), Dir2::X)
                 .with_max_hits(1)
-                .with_max_distance(Self::WALL_CHECKER_MAX_DISTANCE)
+                .with_max_distance(CheckerReach::Wall.distance(profile))
                 .with_query_filter(
                     SpatialQueryFilter::default().with_mask(GameCollisionLayers::Enviroment),
                 ),
             FrontChecker2,
+            CheckerReach::Wall,
         ));
         command.spawn((
             RayCaster::new(
@@ -196,11 +604,12 @@ impl PlayerCheckers {
                 Dir2::NEG_X,
             )
             .with_max_hits(1)
-            .with_max_distance(Self::WALL_CHECKER_MAX_DISTANCE)
+            .with_max_distance(CheckerReach::Wall.distance(profile))
             .with_query_filter(
                 SpatialQueryFilter::default().with_mask(GameCollisionLayers::Enviroment),
             ),
             BackChecker1,
+            CheckerReach::Wall,
         ));
         command.spawn((
             RayCaster::new(
@@ -208,11 +617,19 @@ impl PlayerCheckers {
                 Dir2::NEG_X,
             )
             .with_max_hits(1)
-            .with_max_distance(Self::WALL_CHECKER_MAX_DISTANCE)
+            .with_max_distance(CheckerReach::Wall.distance(profile))
             .with_query_filter(
                 SpatialQueryFilter::default().with_mask(GameCollisionLayers::Enviroment),
             ),
             BackChecker2,
+            CheckerReach::Wall,
+        ));
+        command.spawn((
+            RayCaster::new(Vec2::ZERO, Dir2::NEG_Y)
+                .with_max_hits(1)
+                .with_max_distance(Self::SLOPE_CHECKER_MAX_DISTANCE)
+                .with_query_filter(Self::ground_filter()),
+            SlopeChecker,
         ));
     }
 }
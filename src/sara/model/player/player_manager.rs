@@ -1,27 +1,159 @@
 use super::super::tile::prelude::*;
 use super::*;
+use bevy::app::FixedMainScheduleOrder;
+use bevy::ecs::entity::hash_set::EntityHashSet;
+use bevy::input::InputSystem;
+use bevy::state::state::StateTransitionSteps;
+use bevy::state::state_scoped::clear_state_scoped_entities;
+use std::path::Path;
 
 pub struct PlayerManager;
 impl PlayerManager {
-    const VELOCITY_SPEED: f32 = 120.0;
-    const JUMP_SPEED: f32 = 250.0;
+    const ATTACK_FRAME_TIME: f32 = 0.08;
+    const DASH_FRAME_TIME: f32 = 0.04;
+
+    // a broken save is left alone, the run starts without its abilities
+    fn load_save(mut unlocked: ResMut<UnlockedAbilities>) {
+        match SaveData::read(Path::new(SaveData::FILE_PATH)) {
+            Ok(save) => unlocked.0 = save.abilities,
+            Err(error) => warn!("could not read {}: {error}", SaveData::FILE_PATH),
+        }
+    }
+
+    fn unlock(
+        trigger: Trigger<PlayerUnlock>,
+        mut unlocked: ResMut<UnlockedAbilities>,
+        mut abilities: Single<&mut PlayerAbilities, With<PlayerMarker>>,
+    ) {
+        let ability = trigger.event().0;
+        abilities.0.unlock(ability);
+        if !unlocked.0.unlock(ability) {
+            return;
+        }
+        let save = SaveData {
+            abilities: unlocked.0.clone(),
+        };
+        if let Err(error) = save.write(Path::new(SaveData::FILE_PATH)) {
+            warn!("could not write {}: {error}", SaveData::FILE_PATH);
+        }
+    }
 
     fn init(
         mut command: Commands,
         level_resource: Res<LevelResource>,
         level_config: Res<Assets<LevelAsset>>,
+        movement: Movement,
+        unlocked: Res<UnlockedAbilities>,
+        mut lives: ResMut<PlayerLives>,
         mut next_state: ResMut<NextState<PlayerState>>,
     ) {
-        let entry = level_config.get(&level_resource.data_handle).unwrap().entry;
-        let player = Player::new(Vec3::new(entry.0, entry.1, 2.0));
+        let level_data = level_config.get(&level_resource.data_handle).unwrap();
+        let entry = level_data.entry;
+        lives.respawn = entry.into();
+        let mut abilities = unlocked.0.clone();
+        abilities.extend(&level_data.abilities);
+        let player = Player::new(Vec3::new(entry.0, entry.1, 2.0), abilities);
         command
             .spawn((player, StateScoped(PlayerState::Running)))
-            .with_children(PlayerCheckers::add_to)
+            .with_children(|command| PlayerCheckers::add_to(command, movement.profile()))
             .observe(Self::pause)
-            .observe(Self::hurt);
+            .observe(Self::die)
+            .observe(Self::unlock);
         next_state.set(PlayerState::Running);
     }
 
+    // every death costs a life, the player comes back on the last checkpoint while one is left
+    fn die(
+        _: Trigger<PlayerDied>,
+        mut lives: ResMut<PlayerLives>,
+        mut player: PlayerRespawnQuery,
+        mut next_state: ResMut<NextState<PlayerRunningState>>,
+        mut level_event: EventWriter<LevelPass>,
+        mut next_scene: ResMut<NextState<GameScene>>,
+    ) {
+        lives.lives = lives.lives.saturating_sub(1);
+        if lives.lives == 0 {
+            level_event.write(LevelPass(false));
+            next_scene.set(GameScene::GameOver);
+            return;
+        }
+        let (transform, easing, velocity, hp, hurt) = &mut *player;
+        transform.translation = lives.respawn.extend(transform.translation.z);
+        // drawn on the checkpoint at once, not sliding there from where the player died
+        easing.start = Some(transform.translation);
+        velocity.0 = Vec2::ZERO;
+        **hp = HP::default();
+        // a short grace period so whatever is on the checkpoint can not hit right away
+        hurt.hit();
+        next_state.set(PlayerRunningState::Fall);
+    }
+
+    fn sample_input(input: Res<ButtonInput<KeyCode>>, mut player_input: ResMut<PlayerInput>) {
+        player_input.sample(&input);
+    }
+
+    // a press is seen by the first step after it, the next ones only see the key held
+    fn consume_input(mut player_input: ResMut<PlayerInput>) {
+        player_input.consume();
+    }
+
+    // a level's own gravity wins over the profile's
+    fn apply_gravity(
+        movement: Movement,
+        level_resource: Res<LevelResource>,
+        level_asset: Res<Assets<LevelAsset>>,
+        mut gravity: ResMut<Gravity>,
+    ) {
+        let pull = level_asset
+            .get(&level_resource.data_handle)
+            .and_then(|level_data| level_data.gravity)
+            .unwrap_or(movement.profile().gravity);
+        let pull = Vec2::NEG_Y * pull;
+        if gravity.0 != pull {
+            gravity.0 = pull;
+        }
+    }
+
+    // gravity pulls harder on the way down and the fall speed is capped
+    fn scale_gravity(
+        movement: Movement,
+        mut player: Single<(&mut GravityScale, &mut LinearVelocity), With<PlayerMarker>>,
+    ) {
+        let profile = movement.profile();
+        let (gravity_scale, velocity) = &mut *player;
+        gravity_scale.0 = profile.gravity_scale(velocity.y);
+        velocity.y = velocity.y.max(-profile.terminal_velocity);
+    }
+
+    // the checkers are spawned with the profile distances, an edited profile moves them
+    fn reload_checkers(
+        mut events: EventReader<AssetEvent<MovementProfile>>,
+        movement: Movement,
+        checkers: Query<(&mut RayCaster, &CheckerReach)>,
+    ) {
+        if events.is_empty() {
+            return;
+        }
+        events.clear();
+        for (mut checker, reach) in checkers {
+            checker.max_distance = reach.distance(movement.profile());
+        }
+    }
+
+    fn fall_out(
+        mut command: Commands,
+        level_resource: Res<LevelResource>,
+        level_asset: Res<Assets<LevelAsset>>,
+        player: Single<(Entity, &Transform), With<PlayerMarker>>,
+    ) {
+        let Some(level_data) = level_asset.get(&level_resource.data_handle) else {
+            return;
+        };
+        if player.1.translation.y < level_data.kill_plane() {
+            command.trigger_targets(PlayerDied, player.0);
+        }
+    }
+
     fn pause(
         _: Trigger<PlayerWaitChange>,
         current_state: Res<State<PlayerRunningState>>,
@@ -39,62 +171,411 @@ impl PlayerManager {
         }
     }
 
+    // the worst hazard touched hurts, staying inside one hurts again once invulnerability is over
     fn hurt(
-        trigger: Trigger<OnCollisionStart>,
-        hitbox: Query<&HitBoxMarker>,
-        mut hp: PlayerHPQuery,
-        //mut command: Commands,
-        mut level_event: EventWriter<LevelPass>,
-        mut next_scene: ResMut<NextState<GameScene>>,
+        mut command: Commands,
+        collisions: Collisions,
+        hitboxes: Query<(&HitDamage, &GlobalTransform), With<HitBoxMarker>>,
+        mut player: Single<(Entity, &Transform, &mut HP, &mut PlayerHurt), With<PlayerMarker>>,
+        mut player_linear_velocity_query: PlayerLinearVelocityQueryMut,
+        mut next_state: ResMut<NextState<PlayerRunningState>>,
     ) {
-        if !hitbox.contains(trigger.collider) {
+        let (entity, transform, hp, hurt) = &mut *player;
+        if hurt.is_invulnerable() {
             return;
         }
-        if hp.0 == 1 {
-            level_event.write(LevelPass(false));
-            next_scene.set(GameScene::GameOver);
+        let Some((damage, hazard)) = collisions
+            .entities_colliding_with(*entity)
+            .filter_map(|other| hitboxes.get(other).ok())
+            .max_by_key(|(damage, _)| damage.0)
+        else {
             return;
+        };
+        if hp.0 <= damage.0 {
+            hp.0 = 0;
+            command.trigger_targets(PlayerDied, *entity);
+            return;
+        }
+        hp.0 -= damage.0;
+        hurt.hit();
+        let away = if transform.translation.x < hazard.translation().x {
+            -1.0
+        } else {
+            1.0
+        };
+        player_linear_velocity_query.0 = PlayerHurt::KNOCKBACK * Vec2::new(away, 1.0);
+        next_state.set(PlayerRunningState::Hurt);
+    }
+
+    fn blink(
+        time: Res<Time>,
+        mut player: Single<(&mut PlayerHurt, &mut Sprite), With<PlayerMarker>>,
+    ) {
+        let (hurt, sprite) = &mut *player;
+        hurt.tick(time.delta());
+        sprite.color.set_alpha(hurt.alpha());
+    }
+
+    fn enter_hurt(
+        player_resource: Res<PlayerResource>,
+        mut player_aseprite_param: PlayerAsepriteQuery,
+        mut player_gravity_scale: PlayerGravityScaleQuery,
+    ) {
+        player_gravity_scale.0 = 1.0;
+        let (image_handle, layout_handle) = player_resource
+            .texture_atlas_handles
+            .get(&PlayerAsepriteType::Fall)
+            .unwrap();
+        player_aseprite_param.sprite.image = image_handle.clone();
+        player_aseprite_param.sprite.texture_atlas = Some(TextureAtlas {
+            layout: layout_handle.clone(),
+            index: 0,
+        });
+        *player_aseprite_param.playing = AsepritePlaying(false);
+    }
+
+    fn on_hurt(
+        hurt: Single<&PlayerHurt, With<PlayerMarker>>,
+        mut next_state: ResMut<NextState<PlayerRunningState>>,
+    ) {
+        if hurt.lockout.finished() {
+            next_state.set(PlayerRunningState::Fall);
         }
-        hp.0 -= 1;
-        //command.spawn(PlayerTwinkleTimer::default());
     }
 
+    // a press just before landing or just after leaving the ground still jumps
+    #[allow(clippy::too_many_arguments)]
+    fn jump(
+        time: Res<Time>,
+        windows: Res<JumpWindows>,
+        state: Res<State<PlayerRunningState>>,
+        input: Res<PlayerInput>,
+        movement: Movement,
+        mut jump: Single<&mut PlayerJump, With<PlayerMarker>>,
+        mut next_state: ResMut<NextState<PlayerRunningState>>,
+        mut player_linear_velocity_query: PlayerLinearVelocityQueryMut,
+    ) {
+        let grounded = [PlayerRunningState::Walk, PlayerRunningState::Idle].contains(state.get());
+        if jump.0.update(
+            &windows,
+            time.delta_secs(),
+            grounded,
+            input.jump.just_pressed(),
+        ) {
+            player_linear_velocity_query.y = movement.profile().jump_speed;
+            next_state.set(PlayerRunningState::Jump);
+        }
+    }
+
+    // an attack or a hit drops a pending press and the ground left behind
+    fn forget_jump(mut jump: Single<&mut PlayerJump, With<PlayerMarker>>) {
+        jump.0.reset();
+    }
+
+    // accelerates toward the held direction and brakes harder when turning around,
+    // a wall in the way stops the player at once
+    #[allow(clippy::too_many_arguments)]
     fn handle_input(
+        time: Res<Time>,
+        input: Res<PlayerInput>,
         state: Res<State<PlayerRunningState>>,
-        input: Res<ButtonInput<KeyCode>>,
+        movement: Movement,
         front_wall_query: FrontWallQuery,
         back_wall_query: BackWallQuery,
-        mut sprite: Single<&mut Sprite, With<PlayerMarker>>,
+        mut player: Single<(&mut Sprite, &mut PlayerWallJump, &mut PlayerRun), With<PlayerMarker>>,
+        mut player_linear_velocity_query: PlayerLinearVelocityQueryMut,
+    ) {
+        let (sprite, wall_jump, run) = &mut *player;
+        wall_jump.lockout.tick(time.delta());
+        if !wall_jump.lockout.finished() {
+            return;
+        }
+        let profile = movement.profile();
+        let grounded = [PlayerRunningState::Walk, PlayerRunningState::Idle].contains(state.get());
+        // no platform carries the player in the air, a dash or a knockback keeps its momentum
+        if !grounded {
+            run.0.carry(player_linear_velocity_query.x);
+        }
+        let direction = input.direction();
+        if direction != 0.0 {
+            sprite.flip_x = direction > 0.0;
+        }
+        let speed = run
+            .0
+            .update(profile, direction, grounded, time.delta_secs());
+        if (speed < 0.0 && back_wall_query.iter().any(PlayerCheckers::is_blocked))
+            || (speed > 0.0 && front_wall_query.iter().any(PlayerCheckers::is_blocked))
+        {
+            run.0.stop();
+        }
+        player_linear_velocity_query.x = run.0.speed();
+    }
+
+    // falling while pressing into a wall starts a slide
+    fn start_wall_slide(
+        input: Res<PlayerInput>,
+        ground_query: GroundQuery,
+        front_wall_query: FrontWallQuery,
+        back_wall_query: BackWallQuery,
+        abilities: Single<&PlayerAbilities, With<PlayerMarker>>,
+        mut next_state: ResMut<NextState<PlayerRunningState>>,
+    ) {
+        if !abilities.0.has(Ability::WallSlide) || ground_query.iter().any(|hits| !hits.is_empty())
+        {
+            return;
+        }
+        if PlayerCheckers::wall_side(&front_wall_query, &back_wall_query) == Some(input.direction())
+        {
+            next_state.set(PlayerRunningState::WallSlide);
+        }
+    }
+
+    // the fall is capped for as long as the player keeps pressing into the wall
+    fn on_wall_slide(
+        input: Res<PlayerInput>,
+        ground_query: GroundQuery,
+        front_wall_query: FrontWallQuery,
+        back_wall_query: BackWallQuery,
+        movement: Movement,
         mut next_state: ResMut<NextState<PlayerRunningState>>,
         mut player_linear_velocity_query: PlayerLinearVelocityQueryMut,
     ) {
-        player_linear_velocity_query.x = 0.0;
-        if [PlayerRunningState::Walk, PlayerRunningState::Idle].contains(state.get())
-            && input.just_pressed(KeyCode::Space)
+        if ground_query.iter().any(|hits| !hits.is_empty()) {
+            next_state.set(PlayerRunningState::Idle);
+            return;
+        }
+        if PlayerCheckers::wall_side(&front_wall_query, &back_wall_query) != Some(input.direction())
         {
-            player_linear_velocity_query.y = Self::JUMP_SPEED;
-            next_state.set(PlayerRunningState::Jump);
+            next_state.set(PlayerRunningState::Fall);
+            return;
+        }
+        player_linear_velocity_query.y = player_linear_velocity_query
+            .y
+            .max(-movement.profile().wall_slide_speed);
+    }
+
+    // a jump off a wall in the air pushes the player away from it
+    fn wall_jump(
+        input: Res<PlayerInput>,
+        ground_query: GroundQuery,
+        front_wall_query: FrontWallQuery,
+        back_wall_query: BackWallQuery,
+        mut player: PlayerWallJumpQuery,
+        mut next_state: ResMut<NextState<PlayerRunningState>>,
+        mut player_linear_velocity_query: PlayerLinearVelocityQueryMut,
+    ) {
+        if !input.jump.just_pressed() || ground_query.iter().any(|hits| !hits.is_empty()) {
+            return;
+        }
+        if let NextState::Pending(PlayerRunningState::Jump) = *next_state {
+            return;
+        }
+        let (abilities, jump, wall_jump, sprite) = &mut *player;
+        if !abilities.0.has(Ability::WallJump) {
+            return;
+        }
+        let Some(side) = PlayerCheckers::wall_side(&front_wall_query, &back_wall_query) else {
+            return;
+        };
+        player_linear_velocity_query.0 =
+            Vec2::new(-side * PlayerWallJump::SPEED.x, PlayerWallJump::SPEED.y);
+        wall_jump.lockout.reset();
+        jump.0.reset();
+        sprite.flip_x = side < 0.0;
+        next_state.set(PlayerRunningState::Jump);
+    }
+
+    fn tick_cooldowns(
+        time: Res<Time>,
+        mut cooldowns: Single<&mut PlayerCooldowns, With<PlayerMarker>>,
+    ) {
+        cooldowns.0.tick(time.delta_secs());
+    }
+
+    fn refill_air_moves(mut air_moves: Single<&mut PlayerAirMoves, With<PlayerMarker>>) {
+        **air_moves = PlayerAirMoves::default();
+    }
+
+    // a second jump in the air, once until the player lands again
+    fn double_jump(
+        input: Res<PlayerInput>,
+        ground_query: GroundQuery,
+        movement: Movement,
+        mut player: PlayerDoubleJumpQuery,
+        mut next_state: ResMut<NextState<PlayerRunningState>>,
+        mut player_linear_velocity_query: PlayerLinearVelocityQueryMut,
+    ) {
+        if !input.jump.just_pressed() || ground_query.iter().any(|hits| !hits.is_empty()) {
+            return;
         }
-        if input.any_pressed([KeyCode::KeyA, KeyCode::ArrowLeft])
-            && back_wall_query.iter().all(|hits| hits.is_empty())
+        // a coyote jump or a wall jump comes first
+        if let NextState::Pending(PlayerRunningState::Jump) = *next_state {
+            return;
+        }
+        let (abilities, cooldowns, air_moves, jump) = &mut *player;
+        if !abilities.0.has(Ability::DoubleJump)
+            || !cooldowns.0.is_ready(Ability::DoubleJump)
+            || air_moves.jumped
         {
-            sprite.flip_x = false;
-            player_linear_velocity_query.x = -Self::VELOCITY_SPEED;
             return;
         }
-        if input.any_pressed([KeyCode::KeyD, KeyCode::ArrowRight])
-            && front_wall_query.iter().all(|hits| hits.is_empty())
+        air_moves.jumped = true;
+        cooldowns.0.start(Ability::DoubleJump);
+        jump.0.reset();
+        player_linear_velocity_query.y = movement.profile().double_jump_speed;
+        next_state.set(PlayerRunningState::DoubleJump);
+    }
+
+    // on the ground it is a ground dash, in the air an air dash, both go the way the player faces
+    fn start_dash(
+        input: Res<PlayerInput>,
+        state: Res<State<PlayerRunningState>>,
+        mut player: PlayerDashQuery,
+        mut next_state: ResMut<NextState<PlayerRunningState>>,
+    ) {
+        if !input.dash.just_pressed() {
+            return;
+        }
+        if let NextState::Pending(PlayerRunningState::Jump | PlayerRunningState::DoubleJump) =
+            *next_state
         {
-            sprite.flip_x = true;
-            player_linear_velocity_query.x = Self::VELOCITY_SPEED;
+            return;
+        }
+        let (abilities, cooldowns, air_moves, dash, sprite) = &mut *player;
+        let grounded = [PlayerRunningState::Walk, PlayerRunningState::Idle].contains(state.get());
+        let (ability, next) = if grounded {
+            (Ability::GroundDash, PlayerRunningState::GroundDash)
+        } else if air_moves.dashed {
+            return;
+        } else {
+            (Ability::AirDash, PlayerRunningState::AirDash)
+        };
+        if !abilities.0.has(ability) || !cooldowns.0.is_ready(ability) {
+            return;
+        }
+        air_moves.dashed = !grounded;
+        cooldowns.0.start(ability);
+        // flip_x is set while facing right
+        dash.direction = if sprite.flip_x { 1.0 } else { -1.0 };
+        next_state.set(next);
+    }
+
+    fn enter_dash(
+        state: Res<State<PlayerRunningState>>,
+        player_resource: Res<PlayerResource>,
+        mut dash: Single<&mut PlayerDash, With<PlayerMarker>>,
+        mut player_aseprite_param: PlayerAsepriteQuery,
+        mut player_gravity_scale: PlayerGravityScaleQuery,
+    ) {
+        dash.timer.reset();
+        let (aseprite_type, gravity) = match state.get() {
+            PlayerRunningState::AirDash => (PlayerAsepriteType::Fall, 0.0),
+            _ => (PlayerAsepriteType::Walk, 1.0),
+        };
+        player_gravity_scale.0 = gravity;
+        let (image_handle, layout_handle) = player_resource
+            .texture_atlas_handles
+            .get(&aseprite_type)
+            .unwrap();
+        player_aseprite_param.sprite.image = image_handle.clone();
+        player_aseprite_param.sprite.texture_atlas = Some(TextureAtlas {
+            layout: layout_handle.clone(),
+            index: 0,
+        });
+        *player_aseprite_param.indices = AsepriteIndices::new(0, aseprite_type.frame_count() - 1);
+        *player_aseprite_param.timer = AsepriteTimer(Timer::from_seconds(
+            Self::DASH_FRAME_TIME,
+            TimerMode::Repeating,
+        ));
+        *player_aseprite_param.playing = AsepritePlaying(true);
+    }
+
+    // an air dash holds the height, a ground dash follows the ground like walking
+    fn on_dash(
+        time: Res<Time>,
+        state: Res<State<PlayerRunningState>>,
+        movement: Movement,
+        mut dash: Single<&mut PlayerDash, With<PlayerMarker>>,
+        mut next_state: ResMut<NextState<PlayerRunningState>>,
+        mut player_linear_velocity_query: PlayerLinearVelocityQueryMut,
+    ) {
+        dash.timer.tick(time.delta());
+        if dash.timer.finished() {
+            next_state.set(PlayerRunningState::Fall);
+            return;
+        }
+        let profile = movement.profile();
+        if let PlayerRunningState::AirDash = state.get() {
+            player_linear_velocity_query.0 =
+                Vec2::new(dash.direction * profile.air_dash_speed, 0.0);
+        } else {
+            player_linear_velocity_query.x = dash.direction * profile.ground_dash_speed;
+        }
+    }
+
+    // holding down lets the player drop through one-way platforms
+    fn drop_through(
+        mut command: Commands,
+        input: Res<PlayerInput>,
+        mut player: Single<(Entity, &mut PassThroughPlatform), With<PlayerMarker>>,
+    ) {
+        let pass = if input.down.held() {
+            PassThroughPlatform::Always
+        } else {
+            PassThroughPlatform::ByNormal
+        };
+        if *player.1 != pass {
+            *player.1 = pass;
+            command.queue(WakeUpBody(player.0));
         }
     }
 
+    // a platform the player is passing through is no ground, or a jump through it would land halfway
+    fn ignore_passed_platforms(
+        player: Single<Entity, With<PlayerMarker>>,
+        platforms: Query<(Entity, &OneWayPlatform)>,
+        mut checkers: GroundCheckerQuery,
+    ) {
+        let passed = platforms
+            .iter()
+            .filter(|(_, platform)| platform.0.contains(&*player))
+            .map(|(entity, _)| entity)
+            .collect::<EntityHashSet>();
+        for mut checker in &mut checkers {
+            if checker.query_filter.excluded_entities != passed {
+                checker.query_filter.excluded_entities = passed.clone();
+            }
+        }
+    }
+
+    // keeps the feet on the ground along slopes, without gravity the player does not slide down
+    fn follow_ground(
+        ground_query: GroundQuery,
+        slope_query: SlopeQuery,
+        next_state: Res<NextState<PlayerRunningState>>,
+        mut player_gravity_scale: PlayerGravityScaleQuery,
+        mut player_linear_velocity_query: PlayerLinearVelocityQueryMut,
+    ) {
+        // a jump requested this frame owns the vertical speed
+        if let NextState::Pending(PlayerRunningState::Jump) = *next_state {
+            return;
+        }
+        let grounded = ground_query.iter().any(|hits| !hits.is_empty());
+        let Some(normal) = PlayerCheckers::ground_normal(&slope_query).filter(|_| grounded) else {
+            player_gravity_scale.0 = 1.0;
+            return;
+        };
+        player_gravity_scale.0 = 0.0;
+        player_linear_velocity_query.y = -normal.x / normal.y * player_linear_velocity_query.x;
+    }
+
     fn enter_fall(
         player_resource: Res<PlayerResource>,
         mut player_aseprite_param: PlayerAsepriteQuery,
         mut player_speculative_margin: PlayerSpeculativeMarginQuery,
+        mut player_gravity_scale: PlayerGravityScaleQuery,
     ) {
+        player_gravity_scale.0 = 1.0;
         **player_speculative_margin = SpeculativeMargin::MAX;
         let (image_handle, layout_handle) = player_resource
             .texture_atlas_handles
@@ -108,18 +589,10 @@ impl PlayerManager {
         *player_aseprite_param.playing = AsepritePlaying(false);
     }
 
-    fn on_fall(
-        time: Res<Time>,
-        gravity: Res<Gravity>,
-        ground_query: GroundQuery,
-        mut next_state: ResMut<NextState<PlayerRunningState>>,
-        mut player_linear_velocity_query: PlayerLinearVelocityQueryMut,
-    ) {
+    fn on_fall(ground_query: GroundQuery, mut next_state: ResMut<NextState<PlayerRunningState>>) {
         if ground_query.iter().any(|hits| !hits.is_empty()) {
             next_state.set(PlayerRunningState::Idle);
-            return;
         }
-        player_linear_velocity_query.0 += gravity.0 * time.delta_secs();
     }
 
     fn enter_idle(
@@ -138,21 +611,39 @@ impl PlayerManager {
         *player_aseprite_param.playing = AsepritePlaying(false);
     }
 
+    // standing on a moving platform carries the player along
+    fn platform_velocity(ground_query: &GroundQuery, platforms: &PlatformVelocityQuery) -> Vec2 {
+        ground_query
+            .iter()
+            .flat_map(|hits| hits.iter())
+            .find_map(|hit| platforms.get(hit.entity).ok())
+            .map_or(Vec2::ZERO, |velocity| velocity.0)
+    }
+
+    // on a slope or a platform the velocity is not the player's own, only its run speed counts
     fn on_idle(
-        player_linear_velocity_query: PlayerLinearVelocityQuery,
+        ground_query: GroundQuery,
+        platforms: PlatformVelocityQuery,
+        run: Single<&PlayerRun, With<PlayerMarker>>,
+        mut player_linear_velocity_query: PlayerLinearVelocityQueryMut,
         mut next_state: ResMut<NextState<PlayerRunningState>>,
     ) {
-        if player_linear_velocity_query.x.abs() == Self::VELOCITY_SPEED
-            && player_linear_velocity_query.y.abs() < 1.0
-        {
+        if ground_query.iter().all(|hits| hits.is_empty()) {
+            next_state.set(PlayerRunningState::Fall);
+            return;
+        }
+        if run.0.is_walking() {
             next_state.set(PlayerRunningState::Walk);
         }
+        player_linear_velocity_query.0 += Self::platform_velocity(&ground_query, &platforms);
     }
 
     fn enter_jump(
         player_resource: Res<PlayerResource>,
         mut player_aseprite_param: PlayerAsepriteQuery,
+        mut player_gravity_scale: PlayerGravityScaleQuery,
     ) {
+        player_gravity_scale.0 = 1.0;
         let (image_handle, layout_handle) = player_resource
             .texture_atlas_handles
             .get(&PlayerAsepriteType::Jump)
@@ -168,17 +659,40 @@ impl PlayerManager {
         *player_aseprite_param.playing = AsepritePlaying(true);
     }
 
+    // the jump frames again, played twice as fast
+    fn enter_double_jump(
+        player_resource: Res<PlayerResource>,
+        mut player_aseprite_param: PlayerAsepriteQuery,
+        mut player_gravity_scale: PlayerGravityScaleQuery,
+    ) {
+        player_gravity_scale.0 = 1.0;
+        let (image_handle, layout_handle) = player_resource
+            .texture_atlas_handles
+            .get(&PlayerAsepriteType::Jump)
+            .unwrap();
+        player_aseprite_param.sprite.image = image_handle.clone();
+        player_aseprite_param.sprite.texture_atlas = Some(TextureAtlas {
+            layout: layout_handle.clone(),
+            index: 0,
+        });
+        *player_aseprite_param.indices =
+            AsepriteIndices::new(0, PlayerAsepriteType::Jump.frame_count() - 1);
+        *player_aseprite_param.timer = AsepriteTimer(Timer::from_seconds(0.05, TimerMode::Once));
+        *player_aseprite_param.playing = AsepritePlaying(true);
+    }
+
     fn on_jump(
-        input: Res<ButtonInput<KeyCode>>,
+        input: Res<PlayerInput>,
+        movement: Movement,
         mut player_linear_velocity_query: PlayerLinearVelocityQueryMut,
         mut next_running_state: ResMut<NextState<PlayerRunningState>>,
     ) {
-        if !input.pressed(KeyCode::Space) && player_linear_velocity_query.0.y > 0.0 {
-            player_linear_velocity_query.y = 0.0;
+        if !input.jump.held() {
+            player_linear_velocity_query.y =
+                movement.profile().cut_jump(player_linear_velocity_query.y);
         }
         if player_linear_velocity_query.y <= 0.0 {
             next_running_state.set(PlayerRunningState::Fall);
-            return;
         }
     }
 
@@ -206,17 +720,132 @@ impl PlayerManager {
 
     fn on_walk(
         ground_query: GroundQuery,
-        player_linear_velocity_query: PlayerLinearVelocityQuery,
+        platforms: PlatformVelocityQuery,
+        run: Single<&PlayerRun, With<PlayerMarker>>,
+        mut player_linear_velocity_query: PlayerLinearVelocityQueryMut,
         mut next_state: ResMut<NextState<PlayerRunningState>>,
     ) {
         if ground_query.iter().all(|hits| hits.is_empty()) {
             next_state.set(PlayerRunningState::Fall);
             return;
         }
-        if player_linear_velocity_query.x == 0.0 {
+        if !run.0.is_walking() {
             next_state.set(PlayerRunningState::Idle);
+        }
+        player_linear_velocity_query.0 += Self::platform_velocity(&ground_query, &platforms);
+    }
+
+    fn start_attack(
+        time: Res<Time>,
+        input: Res<PlayerInput>,
+        state: Res<State<PlayerRunningState>>,
+        mut attack: PlayerAttackQuery,
+        mut next_state: ResMut<NextState<PlayerRunningState>>,
+    ) {
+        attack.cooldown.tick(time.delta());
+        if *state.get() != PlayerRunningState::Attack
+            && input.attack.just_pressed()
+            && attack.cooldown.finished()
+        {
+            next_state.set(PlayerRunningState::Attack);
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn enter_attack(
+        mut command: Commands,
+        player_resource: Res<PlayerResource>,
+        ground_query: GroundQuery,
+        player: Single<Entity, With<PlayerMarker>>,
+        mut attack: PlayerAttackQuery,
+        mut player_aseprite_param: PlayerAsepriteQuery,
+        mut player_gravity_scale: PlayerGravityScaleQuery,
+        mut player_linear_velocity_query: PlayerLinearVelocityQueryMut,
+    ) {
+        attack.timer.reset();
+        attack.cooldown.reset();
+        player_gravity_scale.0 = 1.0;
+        // an attack roots the player on the ground, in the air it keeps the momentum
+        if ground_query.iter().any(|hits| !hits.is_empty()) {
+            player_linear_velocity_query.x = 0.0;
+        }
+        let (image_handle, layout_handle) = player_resource
+            .texture_atlas_handles
+            .get(&PlayerAsepriteType::Beat)
+            .unwrap();
+        player_aseprite_param.sprite.image = image_handle.clone();
+        player_aseprite_param.sprite.texture_atlas = Some(TextureAtlas {
+            layout: layout_handle.clone(),
+            index: 0,
+        });
+        *player_aseprite_param.indices =
+            AsepriteIndices::new(0, PlayerAsepriteType::Beat.frame_count() - 1);
+        *player_aseprite_param.timer = AsepriteTimer(Timer::from_seconds(
+            Self::ATTACK_FRAME_TIME,
+            TimerMode::Once,
+        ));
+        *player_aseprite_param.playing = AsepritePlaying(true);
+        // flip_x is set while facing right
+        let facing = if player_aseprite_param.sprite.flip_x {
+            1.0
+        } else {
+            -1.0
+        };
+        command.entity(*player).with_child((
+            AttackHitBox::new(),
+            Transform::from_xyz(facing * AttackHitBox::REACH, 0.0, 0.0),
+            StateScoped(PlayerRunningState::Attack),
+        ));
+    }
+
+    // the swing can not be cancelled, the recovery after it can by jumping or moving
+    #[allow(clippy::too_many_arguments)]
+    fn on_attack(
+        mut command: Commands,
+        time: Res<Time>,
+        input: Res<PlayerInput>,
+        spatial_query: SpatialQuery,
+        ground_query: GroundQuery,
+        movement: Movement,
+        hitboxes: Query<(&GlobalTransform, &mut AttackHitBox)>,
+        mut attack: PlayerAttackQuery,
+        mut next_state: ResMut<NextState<PlayerRunningState>>,
+        mut player_linear_velocity_query: PlayerLinearVelocityQueryMut,
+    ) {
+        attack.timer.tick(time.delta());
+        let filter = SpatialQueryFilter::default()
+            .with_mask([GameCollisionLayers::Enemy, GameCollisionLayers::Enviroment]);
+        let mut recovering = true;
+        for (transform, mut hitbox) in hitboxes {
+            hitbox.timer.tick(time.delta());
+            if !hitbox.is_active() {
+                continue;
+            }
+            recovering = false;
+            for entity in spatial_query.shape_intersections(
+                &hitbox.shape,
+                transform.translation().truncate(),
+                0.0,
+                &filter,
+            ) {
+                if hitbox.hit.insert(entity) {
+                    command.trigger_targets(PlayerAttackHit, entity);
+                }
+            }
+        }
+        if attack.timer.finished() {
+            next_state.set(PlayerRunningState::Fall);
+            return;
+        }
+        if !recovering {
             return;
         }
+        if input.jump.just_pressed() && ground_query.iter().any(|hits| !hits.is_empty()) {
+            player_linear_velocity_query.y = movement.profile().jump_speed;
+            next_state.set(PlayerRunningState::Jump);
+        } else if input.left.held() || input.right.held() {
+            next_state.set(PlayerRunningState::Fall);
+        }
     }
 
     fn render_rays(rays: Query<(&RayCaster, &RayHits)>, mut gizmos: Gizmos) {
@@ -235,43 +864,485 @@ impl PlayerManager {
             );
         }
     }
+
+    // the player's own state changes inside the fixed loop, a state set in one step is entered
+    // before the next however many steps a frame runs, every other state changes once a frame
+    fn add_running_state(app: &mut App) {
+        let mut schedule = Schedule::new(PlayerStateTransition);
+        schedule.configure_sets(
+            (
+                StateTransitionSteps::DependentTransitions,
+                StateTransitionSteps::ExitSchedules,
+                StateTransitionSteps::TransitionSchedules,
+                StateTransitionSteps::EnterSchedules,
+            )
+                .chain(),
+        );
+        PlayerRunningState::register_sub_state_systems(&mut schedule);
+        schedule.add_systems(
+            clear_state_scoped_entities::<PlayerRunningState>
+                .in_set(StateTransitionSteps::ExitSchedules),
+        );
+        app.add_schedule(schedule)
+            .init_resource::<NextState<PlayerRunningState>>()
+            .add_event::<StateTransitionEvent<PlayerRunningState>>();
+        app.world_mut()
+            .resource_mut::<FixedMainScheduleOrder>()
+            .insert_after(FixedPreUpdate, PlayerStateTransition);
+    }
 }
 impl Plugin for PlayerManager {
     fn build(&self, app: &mut App) {
+        Self::add_running_state(app);
         app.add_event::<PlayerWaitChange>()
-            .insert_resource(Gravity(Vec2::new(0.0, -300.0)))
+            .insert_resource(Gravity(Vec2::NEG_Y * MovementProfile::DEFAULT.gravity))
+            .init_resource::<JumpWindows>()
+            .init_resource::<PlayerInput>()
+            .add_systems(PreUpdate, Self::sample_input.after(InputSystem))
+            .add_systems(FixedLast, Self::consume_input)
+            .init_resource::<UnlockedAbilities>()
+            .add_systems(Startup, Self::load_save)
             .add_sub_state::<PlayerState>()
+            .add_systems(OnEnter(GameScene::InGame), |mut command: Commands| {
+                command.insert_resource(PlayerLives::default())
+            })
             .add_systems(OnEnter(PlayerState::Loading), Self::init)
-            .add_sub_state::<PlayerRunningState>()
+            .add_systems(
+                FixedUpdate,
+                Self::apply_gravity.run_if(in_state(GameScene::InGame)),
+            )
             .add_systems(
                 Update,
-                Self::render_rays.run_if(in_state(PlayerState::Running)),
+                Self::reload_checkers.run_if(in_state(PlayerState::Running)),
+            )
+            .add_systems(
+                FixedUpdate,
+                Self::scale_gravity.run_if(
+                    in_state(PlayerRunningState::Fall)
+                        .or(in_state(PlayerRunningState::Jump))
+                        .or(in_state(PlayerRunningState::DoubleJump))
+                        .or(in_state(PlayerRunningState::WallSlide))
+                        .or(in_state(PlayerRunningState::Hurt))
+                        .or(in_state(PlayerRunningState::Attack)),
+                ),
             )
-            .add_systems(OnEnter(PlayerRunningState::Fall), Self::enter_fall)
             .add_systems(
                 Update,
-                Self::handle_input.run_if(
+                Self::render_rays.run_if(in_state(PlayerState::Running)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (Self::drop_through, Self::ignore_passed_platforms).run_if(
                     in_state(PlayerState::Running).and(not(in_state(PlayerRunningState::Wait))),
                 ),
             )
+            .add_systems(OnEnter(PlayerRunningState::Fall), Self::enter_fall)
             .add_systems(
-                Update,
+                FixedUpdate,
+                Self::follow_ground.after(Self::handle_input).run_if(
+                    in_state(PlayerRunningState::Walk)
+                        .or(in_state(PlayerRunningState::Idle))
+                        .or(in_state(PlayerRunningState::GroundDash)),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    Self::handle_input,
+                    Self::jump.after(Self::on_fall).before(Self::follow_ground),
+                )
+                    .run_if(
+                        in_state(PlayerState::Running)
+                            .and(not(in_state(PlayerRunningState::Wait)))
+                            .and(not(in_state(PlayerRunningState::Attack)))
+                            .and(not(in_state(PlayerRunningState::Hurt)))
+                            .and(not(in_state(PlayerRunningState::AirDash)))
+                            .and(not(in_state(PlayerRunningState::GroundDash))),
+                    ),
+            )
+            .add_systems(
+                FixedUpdate,
+                Self::start_dash.after(Self::double_jump).run_if(
+                    in_state(PlayerRunningState::Walk)
+                        .or(in_state(PlayerRunningState::Idle))
+                        .or(in_state(PlayerRunningState::Fall))
+                        .or(in_state(PlayerRunningState::Jump))
+                        .or(in_state(PlayerRunningState::DoubleJump)),
+                ),
+            )
+            .add_systems(
+                OnEnter(PlayerRunningState::AirDash),
+                (Self::enter_dash, Self::forget_jump),
+            )
+            .add_systems(
+                OnEnter(PlayerRunningState::GroundDash),
+                (Self::enter_dash, Self::forget_jump),
+            )
+            .add_systems(
+                FixedUpdate,
+                Self::on_dash.before(Self::follow_ground).run_if(
+                    in_state(PlayerRunningState::AirDash)
+                        .or(in_state(PlayerRunningState::GroundDash)),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                Self::start_attack.after(Self::handle_input).run_if(
+                    in_state(PlayerState::Running)
+                        .and(not(in_state(PlayerRunningState::Wait)))
+                        .and(not(in_state(PlayerRunningState::Hurt))),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    Self::blink,
+                    Self::tick_cooldowns,
+                    Self::hurt.after(Self::start_attack),
+                    Self::fall_out,
+                )
+                    .run_if(
+                        in_state(PlayerState::Running).and(not(in_state(PlayerRunningState::Wait))),
+                    ),
+            )
+            .add_systems(
+                OnEnter(PlayerRunningState::Hurt),
+                (Self::enter_hurt, Self::forget_jump),
+            )
+            .add_systems(
+                FixedUpdate,
+                Self::on_hurt.run_if(in_state(PlayerRunningState::Hurt)),
+            )
+            .add_systems(
+                OnEnter(PlayerRunningState::Attack),
+                (Self::enter_attack, Self::forget_jump),
+            )
+            .add_systems(
+                FixedUpdate,
+                Self::on_attack.run_if(in_state(PlayerRunningState::Attack)),
+            )
+            .add_systems(
+                FixedUpdate,
                 Self::on_fall.run_if(in_state(PlayerRunningState::Fall)),
             )
-            .add_systems(OnEnter(PlayerRunningState::Idle), Self::enter_idle)
             .add_systems(
-                Update,
-                Self::on_idle.run_if(in_state(PlayerRunningState::Idle)),
+                FixedUpdate,
+                Self::start_wall_slide
+                    .after(Self::on_fall)
+                    .before(Self::jump)
+                    .run_if(in_state(PlayerRunningState::Fall)),
             )
-            .add_systems(OnEnter(PlayerRunningState::Walk), Self::enter_walk)
+            .add_systems(OnEnter(PlayerRunningState::WallSlide), Self::enter_fall)
             .add_systems(
-                Update,
-                Self::on_walk.run_if(in_state(PlayerRunningState::Walk)),
+                FixedUpdate,
+                Self::on_wall_slide
+                    .before(Self::jump)
+                    .run_if(in_state(PlayerRunningState::WallSlide)),
+            )
+            .add_systems(
+                FixedUpdate,
+                Self::wall_jump.after(Self::jump).run_if(
+                    in_state(PlayerRunningState::Fall)
+                        .or(in_state(PlayerRunningState::Jump))
+                        .or(in_state(PlayerRunningState::DoubleJump))
+                        .or(in_state(PlayerRunningState::WallSlide)),
+                ),
+            )
+            .add_systems(
+                FixedUpdate,
+                Self::double_jump.after(Self::wall_jump).run_if(
+                    in_state(PlayerRunningState::Fall)
+                        .or(in_state(PlayerRunningState::Jump))
+                        .or(in_state(PlayerRunningState::WallSlide)),
+                ),
+            )
+            .add_systems(
+                OnEnter(PlayerRunningState::Idle),
+                (Self::enter_idle, Self::refill_air_moves),
+            )
+            .add_systems(
+                FixedUpdate,
+                Self::on_idle
+                    .after(Self::follow_ground)
+                    .run_if(in_state(PlayerRunningState::Idle)),
+            )
+            .add_systems(
+                OnEnter(PlayerRunningState::Walk),
+                (Self::enter_walk, Self::refill_air_moves),
+            )
+            .add_systems(
+                FixedUpdate,
+                Self::on_walk
+                    .after(Self::follow_ground)
+                    .run_if(in_state(PlayerRunningState::Walk)),
             )
             .add_systems(OnEnter(PlayerRunningState::Jump), Self::enter_jump)
             .add_systems(
-                Update,
-                Self::on_jump.run_if(in_state(PlayerRunningState::Jump)),
+                OnEnter(PlayerRunningState::DoubleJump),
+                Self::enter_double_jump,
+            )
+            .add_systems(
+                FixedUpdate,
+                Self::on_jump.run_if(
+                    in_state(PlayerRunningState::Jump).or(in_state(PlayerRunningState::DoubleJump)),
+                ),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestGame;
+
+    const FLOOR_ROWS: i32 = 2;
+    const ROWS: usize = 12;
+    const COLS: usize = 40;
+
+    #[derive(Debug, Default, Clone, Copy, PartialEq)]
+    struct Keys {
+        left: bool,
+        right: bool,
+        jump: bool,
+    }
+
+    // the keys held on each fixed step, counted from the first step the player is in control
+    #[derive(Resource)]
+    struct Script {
+        keys: Box<dyn Fn(usize) -> Keys + Send + Sync>,
+        held: Keys,
+    }
+
+    // what each fixed step left the player in
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Step {
+        state: PlayerRunningState,
+        position: Vec2,
+        velocity: Vec2,
+    }
+
+    #[derive(Resource, Default)]
+    struct Trace(Vec<Step>);
+
+    // stands in for the keyboard, a frame samples the real keys before its steps run
+    fn play(mut script: ResMut<Script>, trace: Res<Trace>, mut input: ResMut<PlayerInput>) {
+        let input = &mut *input;
+        let keys = (script.keys)(trace.0.len());
+        for (button, held, was_held) in [
+            (&mut input.left, keys.left, script.held.left),
+            (&mut input.right, keys.right, script.held.right),
+            (&mut input.jump, keys.jump, script.held.jump),
+        ] {
+            button.sample(held, held && !was_held);
+        }
+        script.held = keys;
+    }
+
+    fn record(
+        state: Res<State<PlayerRunningState>>,
+        player: Single<(&Position, &LinearVelocity), With<PlayerMarker>>,
+        mut trace: ResMut<Trace>,
+    ) {
+        trace.0.push(Step {
+            state: *state.get(),
+            position: player.0.0,
+            velocity: player.1.0,
+        });
+    }
+
+    fn cell_center(cell: IVec2) -> (f32, f32) {
+        ((cell.as_vec2() + 0.5) * TileAtlas::TILE_SIZE.as_vec2()).into()
+    }
+
+    // a floor under the first `cols` columns and the player dropped a tile above it
+    fn level(cols: i32) -> LevelAsset {
+        let floor = (0..cols).flat_map(|x| (0..FLOOR_ROWS).map(move |y| IVec2::new(x, y)));
+        let mut level = TestGame::walled_level(ROWS, COLS, floor);
+        level.entry = cell_center(IVec2::new(3, FLOOR_ROWS + 1));
+        level
+    }
+
+    fn run(
+        level: LevelAsset,
+        fps: f64,
+        windows: JumpWindows,
+        steps: usize,
+        keys: impl Fn(usize) -> Keys + Send + Sync + 'static,
+    ) -> Vec<Step> {
+        let mut game = TestGame::new(level, fps);
+        game.spawn_tiles();
+        game.app
+            .insert_resource(windows)
+            .insert_resource(Script {
+                keys: Box::new(keys),
+                held: Keys::default(),
+            })
+            .init_resource::<Trace>()
+            .add_systems(FixedPreUpdate, play)
+            .add_systems(
+                FixedLast,
+                record.run_if(resource_exists::<State<PlayerRunningState>>),
             );
+        game.start();
+        while game.app.world().resource::<Trace>().0.len() < steps {
+            game.update(1);
+        }
+        let mut trace = game.app.world_mut().remove_resource::<Trace>().unwrap().0;
+        trace.truncate(steps);
+        trace
+    }
+
+    fn first(trace: &[Step], from: usize, state: PlayerRunningState) -> Option<usize> {
+        trace[from..]
+            .iter()
+            .position(|step| step.state == state)
+            .map(|step| from + step)
+    }
+
+    // walks right, jumps, turns around mid air and jumps again after landing
+    fn course(step: usize) -> Keys {
+        Keys {
+            left: (100..140).contains(&step),
+            right: (10..90).contains(&step),
+            jump: (40..55).contains(&step) || (150..160).contains(&step),
+        }
+    }
+
+    #[test]
+    fn every_frame_rate_runs_the_same_steps() {
+        let steps = 240;
+        let expected = run(level(COLS as i32), 60.0, default(), steps, course);
+        for state in [
+            PlayerRunningState::Walk,
+            PlayerRunningState::Jump,
+            PlayerRunningState::Idle,
+        ] {
+            assert!(first(&expected, 0, state).is_some(), "never in {state:?}");
+        }
+        for fps in [30.0, 144.0] {
+            let trace = run(level(COLS as i32), fps, default(), steps, course);
+            assert_eq!(trace, expected, "{fps} fps");
+        }
+    }
+
+    #[test]
+    fn a_press_just_after_walking_off_a_ledge_still_jumps() {
+        let walk = |_| Keys {
+            right: true,
+            ..default()
+        };
+        let trace = run(level(10), 60.0, default(), 120, walk);
+        let walked = first(&trace, 0, PlayerRunningState::Walk).unwrap();
+        let off = first(&trace, walked, PlayerRunningState::Fall).unwrap();
+        let press = move |step| Keys {
+            // held on, a tap would cut the jump right away
+            jump: (off..off + 10).contains(&step),
+            ..walk(step)
+        };
+        let trace = run(level(10), 60.0, default(), off + 10, press);
+        assert_eq!(trace[off].state, PlayerRunningState::Fall);
+        let jumped = first(&trace, off, PlayerRunningState::Jump).unwrap();
+        assert!(jumped <= off + 1);
+        assert!(trace[jumped].velocity.y > 0.0);
+        // without the coyote time the ledge is gone by then
+        let windows = JumpWindows {
+            coyote_time: 0.0,
+            ..default()
+        };
+        let trace = run(level(10), 60.0, windows, off + 10, press);
+        assert!(first(&trace, off, PlayerRunningState::Jump).is_none());
+    }
+
+    #[test]
+    fn a_press_just_before_landing_is_buffered() {
+        let trace = run(level(COLS as i32), 60.0, default(), 120, |_| {
+            Keys::default()
+        });
+        let landed = first(&trace, 0, PlayerRunningState::Idle).unwrap();
+        let early = landed - 3;
+        let press = move |step| Keys {
+            jump: (early..early + 10).contains(&step),
+            ..default()
+        };
+        let trace = run(level(COLS as i32), 60.0, default(), landed + 10, press);
+        assert_eq!(trace[early].state, PlayerRunningState::Fall);
+        let jumped = first(&trace, early, PlayerRunningState::Jump).unwrap();
+        assert!(jumped <= landed + 1);
+        // without the buffer the press is gone by the time the player lands
+        let windows = JumpWindows {
+            buffer_time: 0.0,
+            ..default()
+        };
+        let trace = run(level(COLS as i32), 60.0, windows, landed + 10, press);
+        assert!(first(&trace, early, PlayerRunningState::Jump).is_none());
+    }
+
+    #[test]
+    fn a_respawn_is_drawn_on_the_checkpoint_at_once() {
+        let checkpoint = Vec2::from(level(COLS as i32).entry) + Vec2::new(256.0, 0.0);
+        // one of a few steps in a row is the last of its frame, where the interpolation shows
+        for death in 30..34 {
+            let mut game = TestGame::new(level(COLS as i32), 30.0);
+            game.spawn_tiles();
+            game.app
+                .init_resource::<Trace>()
+                .add_systems(
+                    FixedUpdate,
+                    move |trace: Res<Trace>,
+                          player: Single<Entity, With<PlayerMarker>>,
+                          mut command: Commands| {
+                        if trace.0.len() == death {
+                            command.trigger_targets(PlayerDied, *player);
+                        }
+                    },
+                )
+                .add_systems(
+                    FixedLast,
+                    record.run_if(resource_exists::<State<PlayerRunningState>>),
+                );
+            game.start();
+            game.app.world_mut().resource_mut::<PlayerLives>().respawn = checkpoint;
+            while game.app.world().resource::<Trace>().0.len() <= death {
+                game.update(1);
+            }
+            let world = game.app.world_mut();
+            let drawn = world
+                .query_filtered::<&Transform, With<PlayerMarker>>()
+                .single(world)
+                .unwrap()
+                .translation
+                .truncate();
+            assert!(
+                drawn.distance(checkpoint) < 8.0,
+                "drawn at {drawn} after step {death}"
+            );
+        }
+    }
+
+    #[test]
+    fn the_run_is_over_after_as_many_deaths_as_lives() {
+        let mut game = TestGame::new(level(COLS as i32), 60.0);
+        game.spawn_tiles();
+        game.start();
+        let mut deaths = 0;
+        while *game.app.world().resource::<State<GameScene>>().get() == GameScene::InGame {
+            assert!(
+                deaths < PlayerLives::MAX_LIVES,
+                "still playing after {deaths} deaths"
+            );
+            let world = game.app.world_mut();
+            let player = world
+                .query_filtered::<Entity, With<PlayerMarker>>()
+                .single(world)
+                .unwrap();
+            world.trigger_targets(PlayerDied, player);
+            deaths += 1;
+            game.update(1);
+        }
+        assert_eq!(deaths, PlayerLives::MAX_LIVES);
+        assert_eq!(
+            *game.app.world().resource::<State<GameScene>>().get(),
+            GameScene::GameOver
+        );
     }
 }
@@ -1,2 +1,3 @@
+pub use super::enemy::prelude::*;
 pub use super::player::prelude::*;
 pub use super::tile::prelude::*;
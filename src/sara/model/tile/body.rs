@@ -0,0 +1,131 @@
+use super::platform::OneWayPlatform;
+use crate::data::level::*;
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct SolidTileMarker;
+
+#[derive(Component)]
+#[require(HitDamage)]
+pub struct HitBoxMarker;
+
+// HP a hitbox takes from the player
+#[derive(Component)]
+pub struct HitDamage(pub usize);
+impl Default for HitDamage {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+#[derive(Component)]
+pub struct PassBoxMarker;
+
+#[derive(Component)]
+pub struct BreakableTileMarker;
+
+// keeps the player and enemies between the left and right edge of the level
+#[derive(Bundle)]
+pub struct SideWall(
+    Collider,
+    Transform,
+    RigidBody,
+    CollisionLayers,
+    SolidTileMarker,
+);
+impl SideWall {
+    const THICKNESS: f32 = 32.0;
+    // room to jump over the top row without getting around the wall
+    const HEADROOM: f32 = 256.0;
+
+    pub fn pair(bounds: Rect, kill_plane: f32) -> [Self; 2] {
+        let bottom = kill_plane.min(bounds.min.y);
+        let top = bounds.max.y + Self::HEADROOM;
+        [
+            bounds.min.x - Self::THICKNESS / 2.0,
+            bounds.max.x + Self::THICKNESS / 2.0,
+        ]
+        .map(|x| {
+            Self(
+                Collider::rectangle(Self::THICKNESS, top - bottom),
+                Transform::from_xyz(x, (top + bottom) / 2.0, 0.0),
+                RigidBody::Static,
+                CollisionLayers::new(
+                    GameCollisionLayers::Enviroment,
+                    [GameCollisionLayers::Player, GameCollisionLayers::Enemy],
+                ),
+                SolidTileMarker,
+            )
+        })
+    }
+}
+
+#[derive(Bundle)]
+pub struct TileCollider(Collider, Transform, CollisionLayers);
+impl TileCollider {
+    pub fn new(
+        collider: Collider,
+        translation: Vec3,
+        rotation: f32,
+        definition: &TileDefinition,
+    ) -> Self {
+        Self(
+            collider,
+            Transform::from_translation(translation).with_rotation(Quat::from_rotation_z(rotation)),
+            CollisionLayers::new(
+                definition.layer_mask(),
+                [GameCollisionLayers::Player, GameCollisionLayers::Enemy],
+            ),
+        )
+    }
+
+    // one body for a whole run of merged tiles, the sprites are spawned per tile by `TileSprite`
+    pub fn merged(area: Rect, definition: &TileDefinition) -> Self {
+        Self::new(
+            Collider::rectangle(area.width(), area.height()),
+            area.center().extend(0.0),
+            0.0,
+            definition,
+        )
+    }
+
+    pub fn insert_behavior(entity: &mut EntityCommands, definition: &TileDefinition) {
+        if definition.sensor {
+            entity.insert(Sensor);
+        }
+        match definition.behavior {
+            TileBehavior::None => {}
+            TileBehavior::Solid => {
+                entity.insert((
+                    Mass(1000.0),
+                    RigidBody::Static,
+                    Restitution::ZERO,
+                    SolidTileMarker,
+                ));
+            }
+            TileBehavior::Damage => {
+                entity.insert((HitBoxMarker, HitDamage(definition.damage)));
+            }
+            TileBehavior::Exit => {
+                entity.insert((CollisionEventsEnabled, PassBoxMarker));
+            }
+            TileBehavior::Breakable => {
+                entity.insert((
+                    Mass(1000.0),
+                    RigidBody::Static,
+                    Restitution::ZERO,
+                    SolidTileMarker,
+                    BreakableTileMarker,
+                ));
+            }
+            TileBehavior::Platform => {
+                entity.insert((
+                    RigidBody::Static,
+                    Restitution::ZERO,
+                    OneWayPlatform::default(),
+                ));
+            }
+        }
+    }
+}
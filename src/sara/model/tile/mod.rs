@@ -1,12 +1,4 @@
-pub mod hitbox;
-pub mod passbox;
+pub mod body;
+pub mod platform;
 pub mod prelude;
-pub mod wall;
-use super::GameCollisionLayers;
-use crate::data::level::*;
-use bevy::prelude::*;
-
-pub trait Tile {
-    type Output;
-    fn new(translation: Vec3, rotation: f32, level_resource: &Res<LevelResource>) -> Self::Output;
-}
+pub mod sprite;
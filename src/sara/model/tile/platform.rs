@@ -0,0 +1,107 @@
+use crate::data::level::MovingPlatform;
+use avian2d::prelude::*;
+use bevy::ecs::entity::hash_set::EntityHashSet;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+// the bodies currently passing through, from below or dropping down
+#[derive(Component, Default)]
+#[require(ActiveCollisionHooks::MODIFY_CONTACTS)]
+pub struct OneWayPlatform(pub EntityHashSet);
+
+#[derive(Component, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PassThroughPlatform {
+    #[default]
+    ByNormal,
+    Always,
+}
+
+enum OneWayPlatformCommand {
+    Add { platform: Entity, entity: Entity },
+    Remove { platform: Entity, entity: Entity },
+}
+impl Command for OneWayPlatformCommand {
+    fn apply(self, world: &mut World) {
+        match self {
+            Self::Add { platform, entity } => {
+                if let Some(mut one_way) = world.get_mut::<OneWayPlatform>(platform) {
+                    one_way.0.insert(entity);
+                }
+            }
+            Self::Remove { platform, entity } => {
+                if let Some(mut one_way) = world.get_mut::<OneWayPlatform>(platform) {
+                    one_way.0.remove(&entity);
+                }
+            }
+        }
+    }
+}
+
+#[derive(SystemParam)]
+pub struct TileCollisionHooks<'w, 's> {
+    platforms: Query<'w, 's, (&'static OneWayPlatform, &'static GlobalTransform)>,
+    pass_throughs: Query<'w, 's, &'static PassThroughPlatform>,
+}
+impl TileCollisionHooks<'_, '_> {
+    // how much the contact normal, seen from the platform, has to point up to count as landing
+    const LANDING_DOT: f32 = 0.5;
+}
+impl CollisionHooks for TileCollisionHooks<'_, '_> {
+    fn modify_contacts(&self, contacts: &mut ContactPair, commands: &mut Commands) -> bool {
+        let (platform, (one_way, transform), other, sign) =
+            if let Ok(found) = self.platforms.get(contacts.collider1) {
+                (contacts.collider1, found, contacts.collider2, 1.0)
+            } else if let Ok(found) = self.platforms.get(contacts.collider2) {
+                (contacts.collider2, found, contacts.collider1, -1.0)
+            } else {
+                return true;
+            };
+
+        // keep passing until the body is fully out of the platform
+        if one_way.0.contains(&other) {
+            if contacts
+                .manifolds
+                .iter()
+                .any(|manifold| manifold.points.iter().any(|point| point.penetration > 0.0))
+            {
+                return false;
+            }
+            commands.queue(OneWayPlatformCommand::Remove {
+                platform,
+                entity: other,
+            });
+        }
+
+        let up = transform.up().truncate();
+        let landing = self.pass_throughs.get(other).copied().unwrap_or_default()
+            == PassThroughPlatform::ByNormal
+            && contacts.manifolds.iter().all(|manifold| {
+                let normal = manifold.normal * sign;
+                normal != Vec2::ZERO && normal.dot(up) >= Self::LANDING_DOT
+            });
+        if !landing {
+            commands.queue(OneWayPlatformCommand::Add {
+                platform,
+                entity: other,
+            });
+        }
+        landing
+    }
+}
+
+#[derive(Component)]
+pub struct MovingPlatformMarker;
+
+#[derive(Component)]
+pub struct MovingPlatformPath {
+    pub platform: MovingPlatform,
+    pub clock: f32,
+}
+impl MovingPlatformPath {
+    pub fn new(platform: MovingPlatform) -> Self {
+        Self {
+            platform,
+            clock: 0.0,
+        }
+    }
+}
@@ -1,4 +1,3 @@
-pub use super::Tile;
-pub use super::wall::*;
-pub use super::hitbox::*;
-pub use super::passbox::*;
\ No newline at end of file
+pub use super::sprite::*;
+pub use super::body::*;
+pub use super::platform::*;
\ No newline at end of file
@@ -0,0 +1,153 @@
+use super::InGameState;
+use crate::scene::GameScene;
+use crate::{data::prelude::*, model::prelude::*};
+use bevy::prelude::*;
+use bevy::time::Stopwatch;
+
+#[derive(Component)]
+struct HeartMarker(usize);
+
+#[derive(Component)]
+struct LivesTextMarker;
+
+#[derive(Component)]
+struct LevelIdTextMarker;
+
+#[derive(Component)]
+struct RunTimeTextMarker;
+
+// time spent playing this run, paused time does not count
+#[derive(Resource, Default)]
+struct RunTime(Stopwatch);
+
+pub struct Hud;
+impl Hud {
+    const HEART_PATH: &'static str = "images/building/heart.png";
+    const HEART_SIZE: f32 = 24.0;
+    // five hearts for a full HP bar, a heart only partly left is drawn as half
+    const HP_PER_HEART: usize = 40;
+    const HEARTS: usize = HP::MAX_HP.div_ceil(Self::HP_PER_HEART);
+    const HALF_HEART_ALPHA: f32 = 0.55;
+    const EMPTY_HEART_ALPHA: f32 = 0.15;
+    const FONT_SIZE: f32 = 16.0;
+    // above the pause overlay
+    const Z_INDEX: i32 = 1;
+
+    fn init(mut command: Commands, asset_server: Res<AssetServer>) {
+        command.insert_resource(RunTime::default());
+        let heart = asset_server.load(Self::HEART_PATH);
+        command
+            .spawn((
+                Node {
+                    width: Val::Percent(100.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    justify_content: JustifyContent::SpaceBetween,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                GlobalZIndex(Self::Z_INDEX),
+                StateScoped(GameScene::InGame),
+            ))
+            .with_children(|parent| {
+                parent
+                    .spawn(Node {
+                        column_gap: Val::Px(2.0),
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    })
+                    .with_children(|parent| {
+                        for index in 0..Self::HEARTS {
+                            parent.spawn((
+                                ImageNode::new(heart.clone()),
+                                Node {
+                                    width: Val::Px(Self::HEART_SIZE),
+                                    height: Val::Px(Self::HEART_SIZE),
+                                    ..Default::default()
+                                },
+                                HeartMarker(index),
+                            ));
+                        }
+                        parent.spawn((
+                            Text::new(format!("x{}", PlayerLives::MAX_LIVES)),
+                            TextFont {
+                                font_size: Self::FONT_SIZE,
+                                ..Default::default()
+                            },
+                            LivesTextMarker,
+                        ));
+                    });
+                parent.spawn((
+                    Text::new("level"),
+                    TextFont {
+                        font_size: Self::FONT_SIZE,
+                        ..Default::default()
+                    },
+                    LevelIdTextMarker,
+                ));
+                parent.spawn((
+                    Text::new("00:00.0"),
+                    TextFont {
+                        font_size: Self::FONT_SIZE,
+                        ..Default::default()
+                    },
+                    RunTimeTextMarker,
+                ));
+            });
+    }
+
+    fn update_hearts(
+        hp: Single<&HP, (With<PlayerMarker>, Changed<HP>)>,
+        hearts: Query<(&mut ImageNode, &HeartMarker)>,
+    ) {
+        for (mut image, heart) in hearts {
+            let left = hp.0.saturating_sub(heart.0 * Self::HP_PER_HEART);
+            let alpha = if left >= Self::HP_PER_HEART {
+                1.0
+            } else if left > 0 {
+                Self::HALF_HEART_ALPHA
+            } else {
+                Self::EMPTY_HEART_ALPHA
+            };
+            image.color.set_alpha(alpha);
+        }
+    }
+
+    fn update_lives(lives: Res<PlayerLives>, mut text: Single<&mut Text, With<LivesTextMarker>>) {
+        text.0 = format!("x{}", lives.lives);
+    }
+
+    fn update_level_id(
+        level_resource: Res<LevelResource>,
+        mut text: Single<&mut Text, With<LevelIdTextMarker>>,
+    ) {
+        text.0 = format!("level {}", level_resource.id);
+    }
+
+    fn update_run_time(
+        time: Res<Time>,
+        mut run_time: ResMut<RunTime>,
+        mut text: Single<&mut Text, With<RunTimeTextMarker>>,
+    ) {
+        run_time.0.tick(time.delta());
+        let seconds = run_time.0.elapsed_secs();
+        text.0 = format!("{:02}:{:04.1}", (seconds / 60.0) as u32, seconds % 60.0);
+    }
+}
+impl Plugin for Hud {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameScene::InGame), Self::init)
+            .add_systems(
+                Update,
+                (
+                    Self::update_hearts,
+                    Self::update_lives.run_if(resource_exists_and_changed::<PlayerLives>),
+                    Self::update_level_id.run_if(resource_exists_and_changed::<LevelResource>),
+                )
+                    .run_if(in_state(GameScene::InGame)),
+            )
+            .add_systems(
+                Update,
+                Self::update_run_time.run_if(in_state(InGameState::Running)),
+            );
+    }
+}
@@ -65,9 +65,10 @@ impl LevelCamera {
             )),
             RigidBody::Kinematic,
             LinearVelocity::ZERO,
+            TransformInterpolation,
             LimitArea(Area {
-                half_width: ((data.cols * LevelResource::TILE_SIZE.x as usize) >> 1) as f32,
-                half_height: ((data.rows * LevelResource::TILE_SIZE.y as usize) >> 1) as f32,
+                half_width: ((data.cols * TileAtlas::TILE_SIZE.x as usize) >> 1) as f32,
+                half_height: ((data.rows * TileAtlas::TILE_SIZE.y as usize) >> 1) as f32,
             }),
             TrackingArea(Area {
                 half_width: Self::HOVER_AREA_VAL,
@@ -154,6 +155,6 @@ impl LevelCamera {
 impl Plugin for LevelCamera {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(LevelState::Running), Self::init)
-            .add_systems(Update, Self::follow.run_if(Level::is_runnable()));
+            .add_systems(FixedUpdate, Self::follow.run_if(Level::is_runnable()));
     }
 }
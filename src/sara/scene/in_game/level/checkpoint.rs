@@ -0,0 +1,72 @@
+use super::LevelState;
+use crate::{data::prelude::*, model::prelude::*};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+#[derive(Component)]
+pub struct CheckpointMarker;
+
+pub struct Checkpoints;
+impl Checkpoints {
+    const IMAGE_PATH: &'static str = "images/building/statue_princess.png";
+    const SIZE: f32 = 32.0;
+    const Z: f32 = 1.0;
+    const INACTIVE_COLOR: Color = Color::srgb(0.5, 0.5, 0.5);
+
+    fn spawn(
+        mut command: Commands,
+        asset_server: Res<AssetServer>,
+        level_resource: Res<LevelResource>,
+        level_asset: Res<Assets<LevelAsset>>,
+    ) {
+        let level_data = level_asset.get(&level_resource.data_handle).unwrap();
+        let image = asset_server.load(Self::IMAGE_PATH);
+        for checkpoint in &level_data.checkpoints {
+            command
+                .spawn((
+                    Sprite {
+                        image: image.clone(),
+                        color: Self::INACTIVE_COLOR,
+                        custom_size: Some(Vec2::splat(Self::SIZE)),
+                        ..Default::default()
+                    },
+                    Transform::from_translation(Vec2::from(*checkpoint).extend(Self::Z)),
+                    Collider::rectangle(Self::SIZE, Self::SIZE),
+                    Sensor,
+                    CollisionEventsEnabled,
+                    CollisionLayers::new(
+                        GameCollisionLayers::Operation,
+                        GameCollisionLayers::Player,
+                    ),
+                    CheckpointMarker,
+                    StateScoped(LevelState::Running),
+                ))
+                .observe(Self::reach);
+        }
+    }
+
+    // the last checkpoint touched lights up and becomes the respawn position
+    fn reach(
+        trigger: Trigger<OnCollisionStart>,
+        player: Single<Entity, With<PlayerMarker>>,
+        mut lives: ResMut<PlayerLives>,
+        mut checkpoints: Query<(Entity, &Transform, &mut Sprite), With<CheckpointMarker>>,
+    ) {
+        if *player != trigger.collider {
+            return;
+        }
+        for (entity, transform, mut sprite) in &mut checkpoints {
+            if entity == trigger.target() {
+                lives.respawn = transform.translation.truncate();
+                sprite.color = Color::WHITE;
+            } else {
+                sprite.color = Self::INACTIVE_COLOR;
+            }
+        }
+    }
+}
+impl Plugin for Checkpoints {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(LevelState::Running), Self::spawn);
+    }
+}
@@ -1,5 +1,8 @@
 mod ccamera;
+mod checkpoint;
 mod parallax;
+mod pickup;
+mod platform;
 mod tilemap;
 use super::super::GameScene;
 use super::InGameState;
@@ -38,6 +41,7 @@ impl Level {
             .get_load_state(&level_resource.data_handle)
             .unwrap()
             .is_loaded()
+            && asset_server.is_loaded(&level_resource.tiles_handle)
         {
             next_state.set(LevelState::Running);
         }
@@ -61,7 +65,10 @@ impl Plugin for Level {
         app.add_event::<LevelWaitChange>()
             .add_sub_state::<LevelState>()
             .add_plugins(ccamera::LevelCamera)
+            .add_plugins(checkpoint::Checkpoints)
             .add_plugins(parallax::Parallax)
+            .add_plugins(pickup::Pickups)
+            .add_plugins(platform::MovingPlatforms)
             .add_plugins(tilemap::TileMap)
             .add_systems(OnEnter(GameScene::InGame), Self::init)
             .add_systems(Update, Self::ready.run_if(in_state(LevelState::Loading)))
@@ -0,0 +1,83 @@
+use super::{Level, LevelState};
+use crate::{data::prelude::*, model::prelude::*};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+#[derive(Component)]
+struct PickupAbility(Ability);
+
+pub struct Pickups;
+impl Pickups {
+    const SIZE: f32 = 14.0;
+    const Z: f32 = 1.0;
+    // turns per second
+    const SPIN: f32 = 0.5;
+
+    const fn color(ability: Ability) -> Color {
+        match ability {
+            Ability::WallSlide => Color::srgb(0.4, 0.8, 1.0),
+            Ability::WallJump => Color::srgb(0.2, 0.5, 1.0),
+            Ability::DoubleJump => Color::srgb(1.0, 0.9, 0.3),
+            Ability::AirDash => Color::srgb(1.0, 0.4, 0.8),
+            Ability::GroundDash => Color::srgb(1.0, 0.6, 0.2),
+        }
+    }
+
+    // abilities the run already has are not offered again
+    fn spawn(
+        mut command: Commands,
+        level_resource: Res<LevelResource>,
+        level_asset: Res<Assets<LevelAsset>>,
+        unlocked: Res<UnlockedAbilities>,
+    ) {
+        let level_data = level_asset.get(&level_resource.data_handle).unwrap();
+        for pickup in &level_data.pickups {
+            if unlocked.0.has(pickup.ability) {
+                continue;
+            }
+            command
+                .spawn((
+                    Sprite::from_color(Self::color(pickup.ability), Vec2::splat(Self::SIZE)),
+                    Transform::from_translation(Vec2::from(pickup.position).extend(Self::Z)),
+                    Collider::rectangle(Self::SIZE, Self::SIZE),
+                    Sensor,
+                    CollisionEventsEnabled,
+                    CollisionLayers::new(
+                        GameCollisionLayers::Operation,
+                        GameCollisionLayers::Player,
+                    ),
+                    PickupAbility(pickup.ability),
+                    StateScoped(LevelState::Running),
+                ))
+                .observe(Self::reach);
+        }
+    }
+
+    fn spin(time: Res<Time>, pickups: Query<&mut Transform, With<PickupAbility>>) {
+        for mut transform in pickups {
+            transform.rotate_z(Self::SPIN * std::f32::consts::TAU * time.delta_secs());
+        }
+    }
+
+    fn reach(
+        trigger: Trigger<OnCollisionStart>,
+        mut command: Commands,
+        player: Single<Entity, With<PlayerMarker>>,
+        pickups: Query<&PickupAbility>,
+    ) {
+        if *player != trigger.collider {
+            return;
+        }
+        let Ok(pickup) = pickups.get(trigger.target()) else {
+            return;
+        };
+        command.trigger_targets(PlayerUnlock(pickup.0), *player);
+        command.entity(trigger.target()).despawn();
+    }
+}
+impl Plugin for Pickups {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(LevelState::Running), Self::spawn)
+            .add_systems(Update, Self::spin.run_if(Level::is_runnable()));
+    }
+}
@@ -0,0 +1,89 @@
+use super::tilemap::TileMapMarker;
+use super::{Level, LevelState};
+use crate::{data::prelude::*, model::prelude::*};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+pub struct MovingPlatforms;
+impl MovingPlatforms {
+    // how fast a platform pulls back onto its path when the physics step drifted
+    const PATH_CORRECTION: f32 = 8.0;
+
+    fn spawn(
+        mut command: Commands,
+        level_resource: Res<LevelResource>,
+        level_asset: Res<Assets<LevelAsset>>,
+        tile_registry: Res<Assets<TileRegistry>>,
+    ) {
+        let level_data = level_asset.get(&level_resource.data_handle).unwrap();
+        let registry = tile_registry.get(&level_resource.tiles_handle).unwrap();
+        let z = level_data
+            .layer(TileLayer::COLLISION)
+            .map_or(TileLayer::collision().z, |layer| layer.z);
+        for platform in &level_data.platforms {
+            let Some(definition) = registry.get(&platform.kind) else {
+                continue;
+            };
+            if !platform.is_valid() {
+                continue;
+            }
+            let offsets = (0..platform.width)
+                .map(|index| Vec2::new(index as f32 * TileAtlas::TILE_SIZE.as_vec2().x, 0.0))
+                .collect::<Vec<_>>();
+            let start = platform.position_at(0.0).extend(z);
+            let mut entity = command.spawn((
+                Visibility::default(),
+                MovingPlatformPath::new(platform.clone()),
+                MovingPlatformMarker,
+                StateScoped(LevelState::Running),
+            ));
+            if let Some(shape) = &definition.collider {
+                let collider = Collider::compound(
+                    offsets
+                        .iter()
+                        .map(|offset| (*offset, 0.0, shape.collider()))
+                        .collect(),
+                );
+                entity.insert(TileCollider::new(collider, start, 0.0, definition));
+                TileCollider::insert_behavior(&mut entity, definition);
+            } else {
+                entity.insert(Transform::from_translation(start));
+            }
+            // whatever the tile kind, a moving platform is driven by its path
+            entity.insert(RigidBody::Kinematic);
+            entity.with_children(|parent| {
+                for offset in &offsets {
+                    parent.spawn((
+                        TileSprite::new(
+                            offset.extend(0.0),
+                            0.0,
+                            platform.atlas_index,
+                            Some(definition),
+                            &level_resource,
+                        ),
+                        TileMapMarker,
+                    ));
+                }
+            });
+        }
+    }
+
+    fn drive(
+        time: Res<Time>,
+        platforms: Query<(&mut MovingPlatformPath, &Transform, &mut LinearVelocity)>,
+    ) {
+        for (mut path, transform, mut velocity) in platforms {
+            path.clock += time.delta_secs();
+            let target = path.platform.position_at(path.clock);
+            velocity.0 = path.platform.velocity_at(path.clock)
+                + (target - transform.translation.truncate()) * Self::PATH_CORRECTION;
+        }
+    }
+}
+impl Plugin for MovingPlatforms {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(LevelState::Running), Self::spawn)
+            // steered once per physics step, the same path at any frame rate
+            .add_systems(FixedUpdate, Self::drive.run_if(Level::is_runnable()));
+    }
+}
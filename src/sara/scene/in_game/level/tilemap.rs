@@ -1,13 +1,138 @@
+use super::ccamera::LevelCameraMarker;
 use super::{Level, LevelState, LevelWaitChange};
 use crate::scene::GameScene;
 use crate::utils::prelude::*;
 use crate::{data::prelude::*, model::prelude::*};
 use avian2d::prelude::*;
 use bevy::prelude::*;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Component)]
 pub struct TileMapMarker;
 
+#[derive(Component)]
+pub struct TileChunkMarker;
+
+#[derive(Component)]
+pub struct TileLayerMarker;
+
+#[derive(Component)]
+struct TileLayerParallax(f32);
+
+enum TileBody {
+    Merged(Rect),
+    Single(Collider, Vec3, f32),
+}
+
+struct LayerChunks {
+    root: Entity,
+    parallax: f32,
+    tiles: HashMap<IVec2, Vec<TileDescriptor>>,
+    view: Option<IRect>,
+    spawned: HashMap<IVec2, Entity>,
+}
+
+// sprites are streamed per chunk around the camera, colliders per chunk around whatever falls,
+// the player and the wolves, one off screen still needs its ground
+#[derive(Resource, Default)]
+struct TileChunks {
+    registry: TileRegistry,
+    layers: Vec<LayerChunks>,
+    bodies: Vec<(TileBody, TileDefinition)>,
+    chunk_bodies: HashMap<IVec2, Vec<usize>>,
+    near: HashSet<IVec2>,
+    spawned_bodies: HashMap<usize, Entity>,
+    broken: HashSet<usize>,
+}
+impl TileChunks {
+    const VIEW_MARGIN: i32 = 1;
+    const PHYSICS_RADIUS: i32 = 1;
+
+    fn new(command: &mut Commands, level_data: &LevelAsset, registry: &TileRegistry) -> Self {
+        let mut chunks = Self {
+            registry: registry.clone(),
+            ..Default::default()
+        };
+        for layer in &level_data.layers {
+            let root = command
+                .spawn((
+                    Transform::from_xyz(0.0, 0.0, layer.z),
+                    Visibility::default(),
+                    TileLayerParallax(layer.parallax),
+                    TileLayerMarker,
+                    StateScoped(LevelState::Running),
+                ))
+                .id();
+            chunks.layers.push(LayerChunks {
+                root,
+                parallax: layer.parallax,
+                tiles: layer
+                    .chunks()
+                    .into_iter()
+                    .map(|(chunk, descriptors)| (chunk, descriptors.into_iter().cloned().collect()))
+                    .collect(),
+                view: None,
+                spawned: HashMap::new(),
+            });
+        }
+        let (merged, singles) = level_data.tile_colliders(registry);
+        for (definition, rect) in merged {
+            chunks.add_body(
+                TileBody::Merged(TileAtlas::cells_area(rect)),
+                definition,
+                ChunkGrid::chunks_of_cells(rect),
+            );
+        }
+        for (definition, descriptor) in singles {
+            let Some(shape) = &definition.collider else {
+                continue;
+            };
+            let translation = Vec3::new(descriptor.tile_pos.0, descriptor.tile_pos.1, 0.0);
+            let chunk = descriptor.chunk();
+            chunks.add_body(
+                TileBody::Single(shape.collider(), translation, descriptor.rotation),
+                definition,
+                IRect::from_corners(chunk, chunk),
+            );
+        }
+        chunks
+    }
+
+    fn add_body(&mut self, body: TileBody, definition: &TileDefinition, range: IRect) {
+        let index = self.bodies.len();
+        self.bodies.push((body, definition.clone()));
+        for chunk in ChunkGrid::iter(range) {
+            self.chunk_bodies.entry(chunk).or_default().push(index);
+        }
+    }
+
+    fn view(center: Vec2, window_size: Vec2) -> IRect {
+        ChunkGrid::chunks_of_area(Rect::from_center_size(center, window_size))
+            .inflate(Self::VIEW_MARGIN)
+    }
+
+    fn near(centers: impl IntoIterator<Item = Vec2>) -> HashSet<IVec2> {
+        centers
+            .into_iter()
+            .flat_map(|center| {
+                let chunk = ChunkGrid::chunk(TileAtlas::cell(center.into()));
+                ChunkGrid::iter(IRect::from_corners(chunk, chunk).inflate(Self::PHYSICS_RADIUS))
+            })
+            .collect()
+    }
+
+    // the player on the entry and the wolves on their spawns
+    fn starts(level_data: &LevelAsset) -> Vec<Vec2> {
+        level_data
+            .enemies
+            .iter()
+            .filter(|spawn| spawn.is_valid() && matches!(spawn.kind, EnemyKind::Wolf { .. }))
+            .map(|spawn| spawn.position.into())
+            .chain([level_data.entry.into()])
+            .collect()
+    }
+}
+
 pub struct TileMap;
 impl TileMap {
     fn init(mut level_resource: ResMut<LevelResource>, asset_server: Res<AssetServer>) {
@@ -17,44 +142,146 @@ impl TileMap {
 
     fn parse(
         mut command: Commands,
+        window: Single<&Window>,
         level_resource: Res<LevelResource>,
         level_asset: Res<Assets<LevelAsset>>,
+        tile_registry: Res<Assets<TileRegistry>>,
         mut player_state: ResMut<NextState<PlayerState>>,
         mut aseprite_system_state: ResMut<NextState<AsepriteSystemState>>,
     ) {
         let level_data = level_asset.get(&level_resource.data_handle).unwrap();
-        for descriptor in &level_data.data {
-            let translation = Vec3::new(descriptor.tile_pos.0, descriptor.tile_pos.1, 0.0);
-            match descriptor.tile_typ {
-                TileType::Pass => {
-                    command
-                        .spawn((
-                            PassBox::new(translation, descriptor.rotation, &level_resource),
+        let registry = tile_registry.get(&level_resource.tiles_handle).unwrap();
+        let mut chunks = TileChunks::new(&mut command, level_data, registry);
+        // the camera starts centered on the window and the player on the entry
+        for layer in &mut chunks.layers {
+            Self::stream_tiles(
+                &mut command,
+                layer,
+                registry,
+                &level_resource,
+                window.size() / 2.0,
+                window.size(),
+            );
+        }
+        Self::stream_bodies(
+            &mut command,
+            &mut chunks,
+            TileChunks::near(TileChunks::starts(level_data)),
+        );
+        command.insert_resource(chunks);
+        if level_data.side_walls {
+            for wall in SideWall::pair(level_data.bounds(), level_data.kill_plane()) {
+                command.spawn((wall, StateScoped(LevelState::Running)));
+            }
+        }
+        player_state.set(PlayerState::Loading);
+        aseprite_system_state.set(AsepriteSystemState::Running);
+    }
+
+    fn stream_tiles(
+        command: &mut Commands,
+        layer: &mut LayerChunks,
+        registry: &TileRegistry,
+        level_resource: &Res<LevelResource>,
+        camera: Vec2,
+        window_size: Vec2,
+    ) {
+        // a layer root is offset by camera * (1 - parallax), so its tiles show around camera * parallax
+        let view = TileChunks::view(camera * layer.parallax, window_size);
+        if layer.view == Some(view) {
+            return;
+        }
+        layer.view = Some(view);
+        layer.spawned.retain(|chunk, entity| {
+            let keep = view.contains(*chunk);
+            if !keep {
+                command.entity(*entity).despawn();
+            }
+            keep
+        });
+        for chunk in ChunkGrid::iter(view) {
+            if layer.spawned.contains_key(&chunk) {
+                continue;
+            }
+            let Some(descriptors) = layer.tiles.get(&chunk) else {
+                continue;
+            };
+            let entity = command
+                .spawn((
+                    Transform::default(),
+                    Visibility::default(),
+                    TileChunkMarker,
+                    ChildOf(layer.root),
+                ))
+                .with_children(|parent| {
+                    for descriptor in descriptors {
+                        let translation =
+                            Vec3::new(descriptor.tile_pos.0, descriptor.tile_pos.1, 0.0);
+                        parent.spawn((
+                            TileSprite::new(
+                                translation,
+                                descriptor.rotation,
+                                descriptor.atlas_index,
+                                registry.get(&descriptor.kind),
+                                level_resource,
+                            ),
                             TileMapMarker,
-                            StateScoped(LevelState::Running),
-                        ))
-                        .observe(Self::pass);
+                        ));
+                    }
+                })
+                .id();
+            layer.spawned.insert(chunk, entity);
+        }
+    }
+
+    fn stream_bodies(command: &mut Commands, chunks: &mut TileChunks, near: HashSet<IVec2>) {
+        if chunks.near == near {
+            return;
+        }
+        let wanted = near
+            .iter()
+            .filter_map(|chunk| chunks.chunk_bodies.get(chunk))
+            .flatten()
+            .copied()
+            .filter(|index| !chunks.broken.contains(index))
+            .collect::<HashSet<_>>();
+        chunks.near = near;
+        chunks.spawned_bodies.retain(|index, entity| {
+            let keep = wanted.contains(index);
+            if !keep {
+                command.entity(*entity).despawn();
+            }
+            keep
+        });
+        for index in wanted {
+            if chunks.spawned_bodies.contains_key(&index) {
+                continue;
+            }
+            let (body, definition) = &chunks.bodies[index];
+            let collider = match body {
+                TileBody::Merged(area) => TileCollider::merged(*area, definition),
+                TileBody::Single(collider, translation, rotation) => {
+                    TileCollider::new(collider.clone(), *translation, *rotation, definition)
                 }
-                TileType::Wall => {
-                    command.spawn((
-                        Floor::new(translation, descriptor.rotation, &level_resource),
-                        TileMapMarker,
-                        StateScoped(LevelState::Running),
-                    ));
+            };
+            let mut entity =
+                command.spawn((collider, TileMapMarker, StateScoped(LevelState::Running)));
+            TileCollider::insert_behavior(&mut entity, definition);
+            match definition.behavior {
+                TileBehavior::Exit => {
+                    entity.observe(Self::pass);
                 }
-                TileType::Trap => {
-                    command.spawn((
-                        HitBox::new(translation, descriptor.rotation, &level_resource),
-                        TileMapMarker,
-                        StateScoped(LevelState::Running),
-                    ));
+                TileBehavior::Breakable => {
+                    entity.observe(Self::break_tile);
                 }
-            };
+                _ => {}
+            }
+            let entity = entity.id();
+            chunks.spawned_bodies.insert(index, entity);
         }
-        player_state.set(PlayerState::Loading);
-        aseprite_system_state.set(AsepriteSystemState::Running);
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn pass(
         trigger: Trigger<OnCollisionStart>,
         player: Single<Entity, With<PlayerMarker>>,
@@ -78,25 +305,183 @@ impl TileMap {
         }
     }
 
+    // the body goes for good, its sprite leaves with the chunk it is streamed in
+    fn break_tile(
+        trigger: Trigger<PlayerAttackHit>,
+        mut command: Commands,
+        mut chunks: ResMut<TileChunks>,
+    ) {
+        let chunks = &mut *chunks;
+        let Some(index) = chunks
+            .spawned_bodies
+            .iter()
+            .find_map(|(index, entity)| (*entity == trigger.target()).then_some(*index))
+        else {
+            return;
+        };
+        chunks.spawned_bodies.remove(&index);
+        chunks.broken.insert(index);
+        command.entity(trigger.target()).despawn();
+        let (TileBody::Single(_, translation, _), definition) = &chunks.bodies[index] else {
+            return;
+        };
+        let cell = TileAtlas::cell(translation.truncate().into());
+        let chunk = ChunkGrid::chunk(cell);
+        for layer in &mut chunks.layers {
+            let Some(descriptors) = layer.tiles.get_mut(&chunk) else {
+                continue;
+            };
+            let count = descriptors.len();
+            descriptors.retain(|descriptor| {
+                descriptor.cell() != cell || descriptor.kind != definition.name
+            });
+            if descriptors.len() == count {
+                continue;
+            }
+            // streamed again on the next frame without the broken tile
+            if let Some(entity) = layer.spawned.remove(&chunk) {
+                command.entity(entity).despawn();
+            }
+            layer.view = None;
+        }
+    }
+
+    fn parallax(
+        camera: Single<&Transform, (With<LevelCameraMarker>, Without<TileLayerMarker>)>,
+        layers: Query<(&mut Transform, &TileLayerParallax)>,
+    ) {
+        for (mut transform, parallax) in layers {
+            let offset = camera.translation.truncate() * (1.0 - parallax.0);
+            transform.translation = offset.extend(transform.translation.z);
+        }
+    }
+
     fn pause(
         _: Trigger<LevelWaitChange>,
         mut command: Commands,
         player: Single<Entity, With<PlayerMarker>>,
+        enemies: Query<Entity, With<EnemyMarker>>,
         mut playings: Query<&mut AsepritePlaying, With<TileMapMarker>>,
     ) {
         for mut playing in playings.iter_mut() {
             playing.0 = !playing.0;
         }
         command.trigger_targets(PlayerWaitChange, *player);
+        for enemy in enemies {
+            command.trigger_targets(EnemyWaitChange, enemy);
+        }
     }
 
-    fn update() {}
+    fn stream(
+        mut command: Commands,
+        window: Single<&Window>,
+        level_resource: Res<LevelResource>,
+        mut chunks: ResMut<TileChunks>,
+        camera: Single<&Transform, With<LevelCameraMarker>>,
+    ) {
+        let chunks = &mut *chunks;
+        for layer in &mut chunks.layers {
+            Self::stream_tiles(
+                &mut command,
+                layer,
+                &chunks.registry,
+                &level_resource,
+                camera.translation.truncate(),
+                window.size(),
+            );
+        }
+    }
+
+    // on the physics step, a body has to be there before anything falls onto it
+    fn stream_physics(
+        mut command: Commands,
+        mut chunks: ResMut<TileChunks>,
+        player: PlayerTransformQuery,
+        enemies: Query<(&Transform, &RigidBody), With<EnemyMarker>>,
+    ) {
+        let falling = enemies
+            .iter()
+            .filter(|(_, body)| body.is_dynamic())
+            .map(|(transform, _)| transform.translation.truncate());
+        let near = TileChunks::near(falling.chain([player.translation.truncate()]));
+        Self::stream_bodies(&mut command, &mut chunks, near);
+    }
 }
 impl Plugin for TileMap {
     fn build(&self, app: &mut App) {
         app.add_observer(Self::pause)
             .add_systems(OnEnter(LevelState::Loading), Self::init)
             .add_systems(OnEnter(LevelState::Running), Self::parse)
-            .add_systems(Update, Self::update.run_if(Level::is_runnable()));
+            .add_systems(Update, Self::stream.run_if(Level::is_runnable()))
+            .add_systems(
+                FixedUpdate,
+                Self::stream_physics.run_if(Level::is_runnable()),
+            )
+            .add_systems(
+                PostUpdate,
+                Self::parallax
+                    .before(TransformSystem::TransformPropagate)
+                    .run_if(Level::is_runnable()),
+            );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestGame;
+    use bevy::ecs::system::RunSystemOnce;
+
+    // what `TileMap::parse` does for the bodies, the sprites need a window
+    fn load(
+        mut command: Commands,
+        level_resource: Res<LevelResource>,
+        level_asset: Res<Assets<LevelAsset>>,
+        tile_registry: Res<Assets<TileRegistry>>,
+    ) {
+        let level_data = level_asset.get(&level_resource.data_handle).unwrap();
+        let registry = tile_registry.get(&level_resource.tiles_handle).unwrap();
+        let mut chunks = TileChunks::new(&mut command, level_data, registry);
+        TileMap::stream_bodies(
+            &mut command,
+            &mut chunks,
+            TileChunks::near(TileChunks::starts(level_data)),
+        );
+        command.insert_resource(chunks);
+    }
+
+    #[test]
+    fn a_wolf_far_from_the_player_keeps_its_ground() {
+        let chunk = ChunkGrid::CHUNK_CELLS.x;
+        let cols = chunk * 8;
+        // a gap at the end of every chunk, or the whole floor merges into one body
+        let floor = (0..cols)
+            .filter(|x| x % chunk != chunk - 1)
+            .flat_map(|x| (0..2).map(move |y| IVec2::new(x, y)));
+        let mut level = TestGame::walled_level(12, cols as usize, floor);
+        let tile = TileAtlas::TILE_SIZE.as_vec2();
+        level.entry = (tile * Vec2::new(3.5, 3.5)).into();
+        let spawn = tile * Vec2::new((chunk * 5 + chunk / 2) as f32 + 0.5, 3.5);
+        let wolf = EnemyKind::Wolf {
+            patrol: 64.0,
+            speed: 40.0,
+            sight: 0.0,
+        };
+        level.enemies.push(EnemySpawn::new(spawn.into(), wolf));
+        let mut game = TestGame::new(level, 60.0);
+        game.app.world_mut().run_system_once(load).unwrap();
+        game.app.add_systems(FixedUpdate, TileMap::stream_physics);
+        game.start();
+        // long enough to fall out of the level many times over
+        game.update(180);
+        let world = game.app.world_mut();
+        let wolves = world
+            .query_filtered::<&Transform, With<EnemyMarker>>()
+            .iter(world)
+            .map(|transform| transform.translation.truncate())
+            .collect::<Vec<_>>();
+        assert_eq!(wolves.len(), 1, "the wolf fell out of the level");
+        assert!(wolves[0].y > 2.0 * tile.y, "the wolf sank to {}", wolves[0]);
+        assert!(spawn.distance(wolves[0]) < 4.0 * tile.x);
     }
 }
@@ -1,3 +1,4 @@
+mod hud;
 mod level;
 mod pause;
 
@@ -40,6 +41,7 @@ impl Plugin for InGmaeScene {
         app.add_sub_state::<InGameState>()
             .add_plugins(level::Level)
             .add_plugins(pause::Paused)
+            .add_plugins(hud::Hud)
             .add_systems(
                 OnEnter(InGameState::Paused),
                 |mut time: ResMut<Time<Physics>>, mut command: Commands| {
@@ -1,5 +1,5 @@
 use super::GameScene;
-use crate::data::level::LevelInit;
+use crate::data::level::{LevelAsset, LevelInit};
 use bevy::prelude::*;
 
 #[derive(Component)]
@@ -111,7 +111,7 @@ impl StartScene {
         exit_button_query: ExitButtonQuery,
     ) {
         if let Interaction::Pressed = *play_button_query {
-            event_writer.write(LevelInit(0));
+            event_writer.write(LevelInit(LevelAsset::FIRST));
             next_state.set(GameScene::Start.next());
         }
         if let Interaction::Pressed = *load_button_query {
@@ -0,0 +1,192 @@
+use crate::data::prelude::*;
+use crate::model::{self, prelude::*};
+use crate::scene::GameScene;
+use avian2d::prelude::*;
+use bevy::gizmos::GizmoPlugin;
+use bevy::prelude::*;
+use bevy::render::render_resource::Shader;
+use bevy::scene::ScenePlugin;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use std::path::Path;
+use std::time::Duration;
+use strum::IntoEnumIterator;
+
+// the model and the physics on one level without a window, every frame lasts 1 / fps
+pub struct TestGame {
+    pub app: App,
+    frame: Duration,
+}
+impl TestGame {
+    // frames run before the player is in control, the scene changes once a frame
+    const MAX_LOADING_FRAMES: usize = 8;
+
+    pub fn new(level: LevelAsset, fps: f64) -> Self {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            StatesPlugin,
+            AssetPlugin {
+                watch_for_changes_override: Some(false),
+                ..default()
+            },
+            TransformPlugin,
+            ScenePlugin,
+        ))
+        // the gizmos only draw the debug rays, their shaders are never compiled
+        .init_asset::<Shader>()
+        .add_plugins(GizmoPlugin)
+        .add_plugins(PhysicsPlugins::default().with_collision_hooks::<TileCollisionHooks>())
+        .init_asset::<LevelAsset>()
+        .init_asset::<TileRegistry>()
+        .init_asset::<MovementProfile>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .add_event::<LevelPass>()
+        .init_state::<GameScene>()
+        .add_plugins(model::ModelManager);
+        let registry = Self::shipped_registry();
+        let world = app.world_mut();
+        let data_handle = world.resource_mut::<Assets<LevelAsset>>().add(level);
+        let tiles_handle = world.resource_mut::<Assets<TileRegistry>>().add(registry);
+        world.insert_resource(LevelResource {
+            id: LevelAsset::FIRST,
+            texture_handle: default(),
+            layout_handle: default(),
+            data_handle,
+            tiles_handle,
+        });
+        // nothing is drawn, the sprites keep default handles and the profile stays the built-in one
+        world.insert_resource(PlayerResource {
+            texture_atlas_handles: PlayerAsepriteType::iter()
+                .map(|kind| (kind, default()))
+                .collect(),
+            movement_handle: default(),
+        });
+        world.insert_resource(EnemyResource {
+            texture_atlas_handles: EnemyAsepriteType::iter()
+                .map(|kind| (kind, default()))
+                .collect(),
+        });
+        // what `App::run` would do before the first frame
+        app.finish();
+        app.cleanup();
+        Self {
+            app,
+            frame: Duration::from_secs_f64(1.0 / fps),
+        }
+    }
+
+    fn shipped_registry() -> TileRegistry {
+        TileRegistry::read(Path::new(&TileRegistry::file_path())).unwrap()
+    }
+
+    // an empty level with walls on `cells`
+    pub fn walled_level(
+        rows: usize,
+        cols: usize,
+        cells: impl IntoIterator<Item = IVec2>,
+    ) -> LevelAsset {
+        let registry = Self::shipped_registry();
+        let wall = registry.get("wall").unwrap();
+        let mut level = LevelAsset::new(rows, cols);
+        let collision = level
+            .layers
+            .iter_mut()
+            .find(|layer| layer.collider)
+            .unwrap();
+        collision.data = cells
+            .into_iter()
+            .map(|cell| {
+                let center = (cell.as_vec2() + 0.5) * TileAtlas::TILE_SIZE.as_vec2();
+                TileDescriptor::new(center.into(), wall, 0.0)
+            })
+            .collect();
+        level
+    }
+
+    pub fn level(&self) -> &LevelAsset {
+        let world = self.app.world();
+        world
+            .resource::<Assets<LevelAsset>>()
+            .get(&world.resource::<LevelResource>().data_handle)
+            .unwrap()
+    }
+
+    pub fn registry(&self) -> &TileRegistry {
+        let world = self.app.world();
+        world
+            .resource::<Assets<TileRegistry>>()
+            .get(&world.resource::<LevelResource>().tiles_handle)
+            .unwrap()
+    }
+
+    // every tile body of the level at once, the game streams them around what moves
+    pub fn spawn_tiles(&mut self) {
+        let (merged, singles) = self.level().tile_colliders(self.registry());
+        let mut bodies = merged
+            .into_iter()
+            .map(|(definition, rect)| {
+                let area = TileAtlas::cells_area(rect);
+                (TileCollider::merged(area, definition), definition.clone())
+            })
+            .collect::<Vec<_>>();
+        for (definition, descriptor) in singles {
+            let Some(shape) = &definition.collider else {
+                continue;
+            };
+            let translation = Vec3::new(descriptor.tile_pos.0, descriptor.tile_pos.1, 0.0);
+            let collider = TileCollider::new(
+                shape.collider(),
+                translation,
+                descriptor.rotation,
+                definition,
+            );
+            bodies.push((collider, definition.clone()));
+        }
+        let world = self.app.world_mut();
+        for (collider, definition) in bodies {
+            TileCollider::insert_behavior(&mut world.commands().spawn(collider), &definition);
+        }
+        world.flush();
+    }
+
+    // into the level with the player on its entry, no time passes until it is running
+    pub fn start(&mut self) {
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+        self.app.update();
+        // whatever the save on disk unlocked, a test starts without abilities
+        self.app.insert_resource(UnlockedAbilities::default());
+        self.app
+            .world_mut()
+            .resource_mut::<NextState<GameScene>>()
+            .set(GameScene::InGame);
+        self.app.update();
+        self.app
+            .world_mut()
+            .resource_mut::<NextState<PlayerState>>()
+            .set(PlayerState::Loading);
+        for _ in 0..Self::MAX_LOADING_FRAMES {
+            self.app.update();
+            if self.is_running() {
+                break;
+            }
+        }
+        assert!(self.is_running(), "the player never started running");
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(self.frame));
+    }
+
+    fn is_running(&self) -> bool {
+        self.app
+            .world()
+            .get_resource::<State<PlayerState>>()
+            .is_some_and(|state| *state.get() == PlayerState::Running)
+    }
+
+    pub fn update(&mut self, frames: usize) {
+        for _ in 0..frames {
+            self.app.update();
+        }
+    }
+}
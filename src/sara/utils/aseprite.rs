@@ -93,14 +93,14 @@ impl AsepritePlugin {
                 continue;
             }
             timer.tick(time.delta());
-            if timer.just_finished() {
-                if let Some(ref mut atlas) = sprite.texture_atlas {
-                    atlas.index = if atlas.index == indices.last {
-                        indices.first
-                    } else {
-                        atlas.index + 1
-                    };
-                }
+            if timer.just_finished()
+                && let Some(ref mut atlas) = sprite.texture_atlas
+            {
+                atlas.index = if atlas.index == indices.last {
+                    indices.first
+                } else {
+                    atlas.index + 1
+                };
             }
         }
     }
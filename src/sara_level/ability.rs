@@ -0,0 +1,141 @@
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use strum::EnumIter;
+
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    EnumIter,
+    Encode,
+    Decode,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Ability {
+    // pressing into a wall while falling slows the fall down
+    #[default]
+    WallSlide,
+    // jumping off a wall in the air pushes the player away from it
+    WallJump,
+    // one more jump before landing again
+    DoubleJump,
+    // a short burst sideways in the air, gravity is off while it lasts
+    AirDash,
+    // a short burst sideways along the ground
+    GroundDash,
+}
+impl Ability {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::WallSlide => "wall slide",
+            Self::WallJump => "wall jump",
+            Self::DoubleJump => "double jump",
+            Self::AirDash => "air dash",
+            Self::GroundDash => "ground dash",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Self::WallSlide => Self::WallJump,
+            Self::WallJump => Self::DoubleJump,
+            Self::DoubleJump => Self::AirDash,
+            Self::AirDash => Self::GroundDash,
+            Self::GroundDash => Self::WallSlide,
+        }
+    }
+
+    // seconds before the ability can be used again
+    pub const fn cooldown(&self) -> f32 {
+        match self {
+            Self::WallSlide | Self::WallJump => 0.0,
+            Self::DoubleJump => 0.25,
+            Self::AirDash => 0.6,
+            Self::GroundDash => 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AbilitySet(BTreeSet<Ability>);
+impl AbilitySet {
+    pub fn has(&self, ability: Ability) -> bool {
+        self.0.contains(&ability)
+    }
+
+    // true when the ability was not unlocked before
+    pub fn unlock(&mut self, ability: Ability) -> bool {
+        self.0.insert(ability)
+    }
+
+    // true when the ability was unlocked before
+    pub fn lock(&mut self, ability: Ability) -> bool {
+        self.0.remove(&ability)
+    }
+
+    pub fn extend(&mut self, other: &Self) {
+        self.0.extend(other.iter());
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Ability> + '_ {
+        self.0.iter().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+impl FromIterator<Ability> for AbilitySet {
+    fn from_iter<T: IntoIterator<Item = Ability>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+// touching it unlocks `ability` for the rest of the run
+#[derive(Debug, Clone, PartialEq, Encode, Decode, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AbilityPickup {
+    pub position: (f32, f32),
+    pub ability: Ability,
+}
+impl AbilityPickup {
+    pub fn new(position: (f32, f32), ability: Ability) -> Self {
+        Self { position, ability }
+    }
+}
+
+// seconds left before each ability can be used again, a missing one is ready
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AbilityCooldowns(BTreeMap<Ability, f32>);
+impl AbilityCooldowns {
+    pub fn tick(&mut self, delta: f32) {
+        self.0.retain(|_, left| {
+            *left -= delta;
+            *left > 0.0
+        });
+    }
+
+    pub fn is_ready(&self, ability: Ability) -> bool {
+        !self.0.contains_key(&ability)
+    }
+
+    pub fn start(&mut self, ability: Ability) {
+        if ability.cooldown() > 0.0 {
+            self.0.insert(ability, ability.cooldown());
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
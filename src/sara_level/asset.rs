@@ -0,0 +1,246 @@
+use super::ability::{AbilityPickup, AbilitySet};
+use super::codec::LevelCodec;
+use super::enemy::EnemySpawn;
+use super::layer::TileLayer;
+use super::path::MovingPlatform;
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::prelude::*;
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use strum::{EnumIter, IntoEnumIterator};
+use thiserror::Error;
+
+#[derive(Debug, Default, Clone, Copy, EnumIter, PartialEq, Eq)]
+pub enum LevelFormat {
+    #[default]
+    Binary,
+    Text,
+}
+impl LevelFormat {
+    pub const fn extension(&self) -> &'static str {
+        match self {
+            Self::Binary => "sbc",
+            Self::Text => "sbc.json",
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Binary => "sbc",
+            Self::Text => "json",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Binary => Self::Text,
+            Self::Text => Self::Binary,
+        }
+    }
+
+    pub fn from_path(path: &str) -> Option<Self> {
+        Self::iter()
+            .filter(|format| path.ends_with(&(".".to_string() + format.extension())))
+            .max_by_key(|format| format.extension().len())
+    }
+}
+
+// hand edited text must not lose a misspelled or newer field on the way in
+#[derive(Asset, TypePath, Debug, PartialEq, Encode, Decode, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LevelAsset {
+    pub rows: usize,
+    pub cols: usize,
+    pub layers: Vec<TileLayer>,
+    pub entry: (f32, f32),
+    pub next: Option<usize>,
+    pub platforms: Vec<MovingPlatform>,
+    pub enemies: Vec<EnemySpawn>,
+    // respawn positions, the entry counts as the first one
+    pub checkpoints: Vec<(f32, f32)>,
+    // the player is lost this far below the grid
+    pub kill_depth: f32,
+    // solid walls on the left and right edge of the grid
+    pub side_walls: bool,
+    // unlocked for as long as the player is on this level
+    pub abilities: AbilitySet,
+    // unlock their ability for the rest of the run once touched
+    pub pickups: Vec<AbilityPickup>,
+    // replaces the movement profile's gravity, in pixels per second squared
+    pub gravity: Option<f32>,
+}
+impl LevelAsset {
+    pub const ASSET_ROOT: &'static str = "assets/";
+    pub const DATA_DIR: &'static str = "data/";
+    pub const FILE_PREFIX: &'static str = "level";
+    pub const FIRST: usize = 0;
+    pub const DEFAULT_KILL_DEPTH: f32 = 64.0;
+
+    // an empty grid on the default layers, nothing placed on it yet
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            layers: TileLayer::defaults(),
+            entry: (0.0, 0.0),
+            next: None,
+            platforms: Vec::new(),
+            enemies: Vec::new(),
+            checkpoints: Vec::new(),
+            kill_depth: Self::DEFAULT_KILL_DEPTH,
+            side_walls: true,
+            abilities: AbilitySet::default(),
+            pickups: Vec::new(),
+            gravity: None,
+        }
+    }
+
+    pub fn file_name(id: usize, format: LevelFormat) -> String {
+        format!("{}{}.{}", Self::FILE_PREFIX, id, format.extension())
+    }
+
+    pub fn parse_file_name(name: &str) -> Option<(usize, LevelFormat)> {
+        let format = LevelFormat::from_path(name)?;
+        let id = name
+            .strip_prefix(Self::FILE_PREFIX)?
+            .strip_suffix(format.extension())?
+            .strip_suffix('.')?
+            .parse()
+            .ok()?;
+        Some((id, format))
+    }
+
+    pub fn data_dir() -> PathBuf {
+        Path::new(Self::ASSET_ROOT).join(Self::DATA_DIR)
+    }
+
+    pub fn asset_path(id: usize, format: LevelFormat) -> String {
+        Self::DATA_DIR.to_string() + &Self::file_name(id, format)
+    }
+
+    pub fn file_path(id: usize, format: LevelFormat) -> String {
+        Self::ASSET_ROOT.to_string() + &Self::asset_path(id, format)
+    }
+
+    pub fn locate_in(dir: &Path, id: usize) -> Option<LevelFormat> {
+        LevelFormat::iter().find(|format| {
+            std::fs::exists(dir.join(Self::file_name(id, *format))).is_ok_and(|exists| exists)
+        })
+    }
+
+    pub fn locate(id: usize) -> Option<LevelFormat> {
+        Self::locate_in(&Self::data_dir(), id)
+    }
+
+    // the other format is removed, `locate_in` would keep loading a stale copy of it
+    pub fn write_in(
+        &self,
+        dir: &Path,
+        id: usize,
+        format: LevelFormat,
+    ) -> Result<(), LevelAssetError> {
+        std::fs::write(dir.join(Self::file_name(id, format)), self.encode(format)?)?;
+        for other in LevelFormat::iter().filter(|other| *other != format) {
+            match std::fs::remove_file(dir.join(Self::file_name(id, other))) {
+                Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(error.into());
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub fn write(&self, id: usize, format: LevelFormat) -> Result<(), LevelAssetError> {
+        self.write_in(&Self::data_dir(), id, format)
+    }
+
+    pub fn decode(bytes: &[u8], format: LevelFormat) -> Result<Self, LevelAssetError> {
+        match format {
+            LevelFormat::Binary => LevelCodec::decode(bytes),
+            LevelFormat::Text => LevelCodec::decode_text(bytes),
+        }
+    }
+
+    pub fn encode(&self, format: LevelFormat) -> Result<Vec<u8>, LevelAssetError> {
+        match format {
+            LevelFormat::Binary => LevelCodec::encode(self),
+            LevelFormat::Text => LevelCodec::encode_text(self),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum LevelAssetError {
+    #[error("Could not load asset: {0}")]
+    IOError(#[from] std::io::Error),
+    #[error("Truncated sbc header")]
+    TruncatedHeader,
+    #[error(
+        "Unsupported sbc version {0}, newest known version is {newest}",
+        newest = LevelCodec::VERSION
+    )]
+    UnsupportedVersion(u32),
+    #[error("Could not decode sbc version {version}: {source}")]
+    DecodeError {
+        version: u32,
+        source: bincode::error::DecodeError,
+    },
+    #[error("Could not encode sbc: {0}")]
+    EncodeError(#[from] bincode::error::EncodeError),
+    #[error("Could not convert level json: {0}")]
+    JsonError(#[from] serde_json::Error),
+    #[error("Tile {0} has a degenerate collider shape")]
+    InvalidTileShape(String),
+    #[error("Tile {0} is defined more than once")]
+    DuplicateTileName(String),
+    #[error("Movement profile field {0} is out of range")]
+    InvalidMovement(&'static str),
+}
+
+#[derive(Default)]
+pub struct LevelAssetLoader;
+impl AssetLoader for LevelAssetLoader {
+    type Asset = LevelAsset;
+    type Error = LevelAssetError;
+    type Settings = ();
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).await?;
+        LevelAsset::decode(&buf, LevelFormat::Binary)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        const { &[LevelFormat::Binary.extension()] }
+    }
+}
+
+#[derive(Default)]
+pub struct LevelTextAssetLoader;
+impl AssetLoader for LevelTextAssetLoader {
+    type Asset = LevelAsset;
+    type Error = LevelAssetError;
+    type Settings = ();
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).await?;
+        LevelAsset::decode(&buf, LevelFormat::Text)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        const { &[LevelFormat::Text.extension()] }
+    }
+}
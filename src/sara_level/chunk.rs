@@ -0,0 +1,54 @@
+use super::layer::TileLayer;
+use super::tile::{TileAtlas, TileDescriptor};
+use bevy::prelude::*;
+use std::collections::HashMap;
+
+// a square block of cells, the unit the tilemap is spawned and despawned in
+pub struct ChunkGrid;
+impl ChunkGrid {
+    pub const CHUNK_CELLS: IVec2 = IVec2::splat(16);
+
+    pub fn chunk(cell: IVec2) -> IVec2 {
+        cell.div_euclid(Self::CHUNK_CELLS)
+    }
+
+    // chunks touched by a rect of cells with an exclusive max, as an inclusive range
+    pub fn chunks_of_cells(cells: IRect) -> IRect {
+        IRect::from_corners(
+            Self::chunk(cells.min),
+            Self::chunk((cells.max - IVec2::ONE).max(cells.min)),
+        )
+    }
+
+    // chunks touched by an area in world coordinates, as an inclusive range
+    pub fn chunks_of_area(area: Rect) -> IRect {
+        IRect::from_corners(
+            Self::chunk(TileAtlas::cell(area.min.into())),
+            Self::chunk(TileAtlas::cell(area.max.into())),
+        )
+    }
+
+    pub fn iter(range: IRect) -> impl Iterator<Item = IVec2> {
+        (range.min.y..=range.max.y)
+            .flat_map(move |y| (range.min.x..=range.max.x).map(move |x| IVec2::new(x, y)))
+    }
+}
+
+impl TileDescriptor {
+    pub fn chunk(&self) -> IVec2 {
+        ChunkGrid::chunk(self.cell())
+    }
+}
+
+impl TileLayer {
+    pub fn chunks(&self) -> HashMap<IVec2, Vec<&TileDescriptor>> {
+        let mut chunks = HashMap::<_, Vec<_>>::new();
+        for descriptor in &self.data {
+            chunks
+                .entry(descriptor.chunk())
+                .or_default()
+                .push(descriptor);
+        }
+        chunks
+    }
+}
@@ -0,0 +1,63 @@
+mod v0;
+use super::asset::{LevelAsset, LevelAssetError};
+use bincode::{Decode, config};
+
+pub struct LevelCodec;
+impl LevelCodec {
+    // 0xFF can never start a headerless (version 0) file: bincode uses it as an invalid varint tag.
+    pub const MAGIC: [u8; 4] = [0xFF, b'S', b'B', b'C'];
+    pub const VERSION: u32 = 1;
+    const HEADER_SIZE: usize = Self::MAGIC.len() + size_of::<u32>();
+
+    fn split_header(bytes: &[u8]) -> Result<(u32, &[u8]), LevelAssetError> {
+        if !bytes.starts_with(&Self::MAGIC) {
+            return Ok((0, bytes));
+        }
+        if bytes.len() < Self::HEADER_SIZE {
+            return Err(LevelAssetError::TruncatedHeader);
+        }
+        let (header, payload) = bytes.split_at(Self::HEADER_SIZE);
+        let version = u32::from_le_bytes(header[Self::MAGIC.len()..].try_into().unwrap());
+        Ok((version, payload))
+    }
+
+    fn decode_payload<T: Decode<()>>(version: u32, payload: &[u8]) -> Result<T, LevelAssetError> {
+        bincode::decode_from_slice(payload, config::standard())
+            .map(|(asset, _)| asset)
+            .map_err(|source| LevelAssetError::DecodeError { version, source })
+    }
+
+    pub fn version(bytes: &[u8]) -> Result<u32, LevelAssetError> {
+        Ok(Self::split_header(bytes)?.0)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<LevelAsset, LevelAssetError> {
+        let (version, payload) = Self::split_header(bytes)?;
+        match version {
+            0 => {
+                let v0 = Self::decode_payload::<v0::LevelAsset>(version, payload)?;
+                Ok(v0.into())
+            }
+            Self::VERSION => Self::decode_payload(version, payload),
+            _ => Err(LevelAssetError::UnsupportedVersion(version)),
+        }
+    }
+
+    pub fn encode(asset: &LevelAsset) -> Result<Vec<u8>, LevelAssetError> {
+        let mut buf = Vec::from(Self::MAGIC);
+        buf.extend_from_slice(&Self::VERSION.to_le_bytes());
+        buf.extend(bincode::encode_to_vec(asset, config::standard())?);
+        Ok(buf)
+    }
+
+    // text files only ever had the current layout
+    pub fn decode_text(bytes: &[u8]) -> Result<LevelAsset, LevelAssetError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    pub fn encode_text(asset: &LevelAsset) -> Result<Vec<u8>, LevelAssetError> {
+        let mut buf = serde_json::to_vec_pretty(asset)?;
+        buf.push(b'\n');
+        Ok(buf)
+    }
+}
@@ -0,0 +1,73 @@
+// Headerless files written before the sbc container existed. Frozen: never edit these types.
+use crate::{asset, layer, tile};
+use bincode::Decode;
+
+#[derive(Decode)]
+enum TileType {
+    Wall,
+    Pass,
+    Trap,
+}
+impl TileType {
+    // the old types are the kinds the shipped tiles.def.json starts with
+    const fn kind(&self) -> &'static str {
+        match self {
+            Self::Wall => "wall",
+            Self::Pass => "pass",
+            Self::Trap => "trap",
+        }
+    }
+
+    // and the sprite each type used to be drawn with
+    const fn atlas_index(&self) -> usize {
+        match self {
+            Self::Wall => 36,
+            Self::Pass => 0,
+            Self::Trap => 194,
+        }
+    }
+}
+
+#[derive(Decode)]
+struct TileDescriptor {
+    tile_pos: (f32, f32),
+    tile_typ: TileType,
+    rotation: f32,
+}
+
+#[derive(Decode)]
+pub struct LevelAsset {
+    rows: usize,
+    cols: usize,
+    data: Vec<TileDescriptor>,
+    entry: (f32, f32),
+    next: Option<usize>,
+}
+
+impl From<TileDescriptor> for tile::TileDescriptor {
+    fn from(value: TileDescriptor) -> Self {
+        Self {
+            tile_pos: value.tile_pos,
+            kind: value.tile_typ.kind().to_string(),
+            rotation: value.rotation,
+            atlas_index: value.tile_typ.atlas_index(),
+        }
+    }
+}
+
+// every old tile had a collider, so they all land on the collision layer
+impl From<LevelAsset> for asset::LevelAsset {
+    fn from(value: LevelAsset) -> Self {
+        let data = value.data.into_iter().map(Into::into).collect();
+        Self {
+            layers: vec![
+                layer::TileLayer::background(),
+                layer::TileLayer::collision().with_data(data),
+                layer::TileLayer::foreground(),
+            ],
+            entry: value.entry,
+            next: value.next,
+            ..Self::new(value.rows, value.cols)
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use bevy::prelude::*;
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+pub enum FlightPattern {
+    // weaves around its spawn
+    #[default]
+    Sine,
+    // hangs on its spawn and swoops at the player below
+    Dive,
+}
+impl FlightPattern {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Sine => Self::Dive,
+            Self::Dive => Self::Sine,
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Sine => "sine",
+            Self::Dive => "dive",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub enum EnemyKind {
+    // walks `patrol` pixels either side of its spawn, charges the player within `sight`
+    Wolf {
+        patrol: f32,
+        speed: f32,
+        sight: f32,
+    },
+    // flies `range` pixels around its spawn
+    Bat {
+        pattern: FlightPattern,
+        range: f32,
+        speed: f32,
+    },
+}
+impl EnemyKind {
+    pub const WOLF: Self = Self::Wolf {
+        patrol: 96.0,
+        speed: 48.0,
+        sight: 128.0,
+    };
+    pub const BAT: Self = Self::Bat {
+        pattern: FlightPattern::Sine,
+        range: 64.0,
+        speed: 48.0,
+    };
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Wolf { .. } => "wolf",
+            Self::Bat { .. } => "bat",
+        }
+    }
+
+    // wolf, sine bat, diving bat
+    pub fn next(&self) -> Self {
+        match *self {
+            Self::Wolf { .. } => Self::BAT,
+            Self::Bat {
+                pattern: FlightPattern::Sine,
+                range,
+                speed,
+            } => Self::Bat {
+                pattern: FlightPattern::Dive,
+                range,
+                speed,
+            },
+            Self::Bat { .. } => Self::WOLF,
+        }
+    }
+}
+
+impl Default for EnemyKind {
+    fn default() -> Self {
+        Self::WOLF
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EnemySpawn {
+    pub position: (f32, f32),
+    pub kind: EnemyKind,
+}
+impl EnemySpawn {
+    // how high a sine flight weaves, relative to its range
+    const SINE_HEIGHT: f32 = 0.25;
+
+    pub fn new(position: (f32, f32), kind: EnemyKind) -> Self {
+        Self { position, kind }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        match self.kind {
+            EnemyKind::Wolf {
+                patrol,
+                speed,
+                sight,
+            } => patrol >= 0.0 && speed > 0.0 && sight >= 0.0,
+            EnemyKind::Bat { range, speed, .. } => range > 0.0 && speed > 0.0,
+        }
+    }
+
+    // a figure eight, across the range at `speed` and twice as fast up and down
+    fn sine(&self, time: f32) -> Option<(Vec2, Vec2)> {
+        let EnemyKind::Bat {
+            pattern: FlightPattern::Sine,
+            range,
+            speed,
+        } = self.kind
+        else {
+            return None;
+        };
+        if !self.is_valid() {
+            return None;
+        }
+        let omega = speed / range;
+        let height = range * Self::SINE_HEIGHT;
+        let phase = omega * time;
+        Some((
+            Vec2::new(range * phase.sin(), height * (2.0 * phase).sin()),
+            Vec2::new(
+                range * omega * phase.cos(),
+                2.0 * height * omega * (2.0 * phase).cos(),
+            ),
+        ))
+    }
+
+    // only sine flights follow a fixed path, anything else stays on its spawn
+    pub fn flight_position_at(&self, time: f32) -> Vec2 {
+        Vec2::from(self.position) + self.sine(time).map_or(Vec2::ZERO, |(offset, _)| offset)
+    }
+
+    pub fn flight_velocity_at(&self, time: f32) -> Vec2 {
+        self.sine(time).map_or(Vec2::ZERO, |(_, velocity)| velocity)
+    }
+}
@@ -0,0 +1,70 @@
+use super::asset::LevelAsset;
+use super::tile::TileDescriptor;
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Encode, Decode, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TileLayer {
+    pub name: String,
+    pub z: f32,
+    // 1.0 moves with the world, smaller values lag behind the camera
+    pub parallax: f32,
+    pub collider: bool,
+    pub data: Vec<TileDescriptor>,
+}
+impl TileLayer {
+    pub const BACKGROUND: &'static str = "background";
+    pub const COLLISION: &'static str = "collision";
+    pub const FOREGROUND: &'static str = "foreground";
+
+    pub fn new(name: &str, z: f32, parallax: f32, collider: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            z,
+            parallax,
+            collider,
+            data: Vec::new(),
+        }
+    }
+
+    // the player is drawn at z 2.0, between the collision and the foreground layer
+    pub fn background() -> Self {
+        Self::new(Self::BACKGROUND, 0.5, 1.0, false)
+    }
+
+    pub fn collision() -> Self {
+        Self::new(Self::COLLISION, 1.0, 1.0, true)
+    }
+
+    pub fn foreground() -> Self {
+        Self::new(Self::FOREGROUND, 2.5, 1.0, false)
+    }
+
+    pub fn defaults() -> Vec<Self> {
+        vec![Self::background(), Self::collision(), Self::foreground()]
+    }
+
+    pub fn with_data(mut self, data: Vec<TileDescriptor>) -> Self {
+        self.data = data;
+        self
+    }
+}
+
+impl LevelAsset {
+    pub fn layer(&self, name: &str) -> Option<&TileLayer> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    pub fn tiles(&self) -> impl Iterator<Item = &TileDescriptor> {
+        self.layers.iter().flat_map(|layer| &layer.data)
+    }
+
+    // only these tiles get physics in game
+    pub fn collision_tiles(&self) -> impl Iterator<Item = &TileDescriptor> {
+        self.layers
+            .iter()
+            .filter(|layer| layer.collider)
+            .flat_map(|layer| &layer.data)
+    }
+}
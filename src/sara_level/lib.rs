@@ -0,0 +1,28 @@
+pub mod ability;
+pub mod asset;
+pub mod chunk;
+pub mod codec;
+pub mod enemy;
+pub mod layer;
+pub mod merge;
+pub mod movement;
+pub mod path;
+pub mod prelude;
+pub mod registry;
+pub mod save;
+pub mod tile;
+pub mod validate;
+use bevy::prelude::*;
+
+pub struct LevelFormatPlugin;
+impl Plugin for LevelFormatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<asset::LevelAsset>()
+            .init_asset_loader::<asset::LevelAssetLoader>()
+            .init_asset_loader::<asset::LevelTextAssetLoader>()
+            .init_asset::<registry::TileRegistry>()
+            .init_asset_loader::<registry::TileRegistryLoader>()
+            .init_asset::<movement::MovementProfile>()
+            .init_asset_loader::<movement::MovementProfileLoader>();
+    }
+}
@@ -0,0 +1,104 @@
+use super::asset::LevelAsset;
+use super::registry::{TileBehavior, TileDefinition, TileRegistry, TileShape};
+use super::tile::{TileAtlas, TileDescriptor};
+use bevy::prelude::*;
+use std::collections::{BTreeMap, BTreeSet};
+
+pub struct CellMerger;
+impl CellMerger {
+    // Greedy: grow each rectangle right along its row, then up while the whole row segment is free.
+    pub fn merge(cells: impl IntoIterator<Item = IVec2>) -> Vec<IRect> {
+        let mut free = cells
+            .into_iter()
+            .map(|cell| (cell.y, cell.x))
+            .collect::<BTreeSet<_>>();
+        let mut rects = Vec::new();
+        while let Some((y, x)) = free.pop_first() {
+            let mut width = 1;
+            while free.remove(&(y, x + width)) {
+                width += 1;
+            }
+            let mut height = 1;
+            while (x..x + width).all(|col| free.contains(&(y + height, col))) {
+                for col in x..x + width {
+                    free.remove(&(y + height, col));
+                }
+                height += 1;
+            }
+            rects.push(IRect::new(x, y, x + width, y + height));
+        }
+        rects
+    }
+}
+
+impl TileAtlas {
+    pub fn cells_area(rect: IRect) -> Rect {
+        let size = Self::TILE_SIZE.as_ivec2();
+        Rect::from_corners((rect.min * size).as_vec2(), (rect.max * size).as_vec2())
+    }
+}
+
+impl TileDescriptor {
+    // a square collider turned by a multiple of 90 degrees still fills its cell exactly
+    pub fn is_axis_aligned(&self) -> bool {
+        let turns = self.rotation / std::f32::consts::FRAC_PI_2;
+        (turns - turns.round()).abs() < 1e-3
+    }
+}
+
+impl TileDefinition {
+    // solid, cell sized boxes of one kind can share a single body
+    pub fn is_mergeable(&self) -> bool {
+        let size = TileAtlas::TILE_SIZE.as_vec2();
+        self.behavior == TileBehavior::Solid
+            && !self.sensor
+            && self.collider
+                == Some(TileShape::Rectangle {
+                    width: size.x,
+                    height: size.y,
+                    offset: (0.0, 0.0),
+                })
+    }
+}
+
+impl LevelAsset {
+    // merged collision layer rectangles in cell coordinates, plus the tiles that need a body of
+    // their own; tiles of unknown kinds or without a collider are left out
+    #[allow(clippy::type_complexity)]
+    pub fn tile_colliders<'a>(
+        &'a self,
+        registry: &'a TileRegistry,
+    ) -> (
+        Vec<(&'a TileDefinition, IRect)>,
+        Vec<(&'a TileDefinition, &'a TileDescriptor)>,
+    ) {
+        let mut cells = BTreeMap::<&str, Vec<IVec2>>::new();
+        let mut single = Vec::new();
+        for descriptor in self.collision_tiles() {
+            let Some(definition) = registry.get(&descriptor.kind) else {
+                continue;
+            };
+            if definition.collider.is_none() {
+                continue;
+            }
+            if definition.is_mergeable() && descriptor.is_axis_aligned() {
+                cells
+                    .entry(&definition.name)
+                    .or_default()
+                    .push(descriptor.cell());
+            } else {
+                single.push((definition, descriptor));
+            }
+        }
+        let merged = cells
+            .into_iter()
+            .flat_map(|(kind, cells)| {
+                let definition = registry.get(kind).unwrap();
+                CellMerger::merge(cells)
+                    .into_iter()
+                    .map(move |rect| (definition, rect))
+            })
+            .collect();
+        (merged, single)
+    }
+}
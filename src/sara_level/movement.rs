@@ -0,0 +1,168 @@
+use super::asset::{LevelAsset, LevelAssetError};
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// how the player moves, tuned in a json file that is reloaded while the game runs,
+// a misspelled field is an error instead of silently keeping its default
+#[derive(Asset, TypePath, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MovementProfile {
+    // pixels per second
+    pub walk_speed: f32,
+    pub jump_speed: f32,
+    pub double_jump_speed: f32,
+    pub wall_slide_speed: f32,
+    pub air_dash_speed: f32,
+    pub ground_dash_speed: f32,
+    // pixels per second squared: toward the walk speed, friction once nothing is held
+    // and braking while pressing against the current motion
+    pub acceleration: f32,
+    pub deceleration: f32,
+    pub turn_deceleration: f32,
+    // share of the acceleration and deceleration left in the air
+    pub air_control: f32,
+    // pixels per second, standing turns into walking at the first and back below the second
+    pub walk_start_speed: f32,
+    pub walk_stop_speed: f32,
+    // pixels per second squared, a level can override it
+    pub gravity: f32,
+    pub rise_gravity_scale: f32,
+    pub fall_gravity_scale: f32,
+    // fastest fall in pixels per second
+    pub terminal_velocity: f32,
+    // share of the jump speed kept when jump is let go early
+    pub jump_cut: f32,
+    // how far below the feet ground counts, and how far beside the body a wall does
+    pub floor_check_distance: f32,
+    pub wall_check_distance: f32,
+}
+impl MovementProfile {
+    pub const ASSET_PATH: &'static str = "data/player.move.json";
+    pub const EXTENSION: &'static str = "move.json";
+    pub const DEFAULT: Self = Self {
+        walk_speed: 120.0,
+        jump_speed: 250.0,
+        double_jump_speed: 220.0,
+        wall_slide_speed: 40.0,
+        air_dash_speed: 260.0,
+        ground_dash_speed: 240.0,
+        acceleration: 1800.0,
+        deceleration: 2400.0,
+        turn_deceleration: 3600.0,
+        air_control: 0.75,
+        walk_start_speed: 30.0,
+        walk_stop_speed: 10.0,
+        gravity: 300.0,
+        rise_gravity_scale: 1.0,
+        fall_gravity_scale: 2.0,
+        terminal_velocity: 480.0,
+        jump_cut: 0.0,
+        floor_check_distance: 3.0,
+        wall_check_distance: 6.0,
+    };
+
+    pub fn file_path() -> String {
+        LevelAsset::ASSET_ROOT.to_string() + Self::ASSET_PATH
+    }
+
+    // the first field out of range, if any
+    fn invalid_field(&self) -> Option<&'static str> {
+        let positive = [
+            ("walk_speed", self.walk_speed),
+            ("jump_speed", self.jump_speed),
+            ("double_jump_speed", self.double_jump_speed),
+            ("wall_slide_speed", self.wall_slide_speed),
+            ("air_dash_speed", self.air_dash_speed),
+            ("ground_dash_speed", self.ground_dash_speed),
+            ("acceleration", self.acceleration),
+            ("deceleration", self.deceleration),
+            ("turn_deceleration", self.turn_deceleration),
+            ("walk_start_speed", self.walk_start_speed),
+            ("gravity", self.gravity),
+            ("terminal_velocity", self.terminal_velocity),
+            ("floor_check_distance", self.floor_check_distance),
+            ("wall_check_distance", self.wall_check_distance),
+        ];
+        let non_negative = [
+            ("rise_gravity_scale", self.rise_gravity_scale),
+            ("fall_gravity_scale", self.fall_gravity_scale),
+            ("walk_stop_speed", self.walk_stop_speed),
+        ];
+        let shares = [
+            ("air_control", self.air_control),
+            ("jump_cut", self.jump_cut),
+        ];
+        positive
+            .into_iter()
+            .find(|(_, value)| !(value.is_finite() && *value > 0.0))
+            .or_else(|| {
+                non_negative
+                    .into_iter()
+                    .find(|(_, value)| !(value.is_finite() && *value >= 0.0))
+            })
+            .or_else(|| {
+                shares
+                    .into_iter()
+                    .find(|(_, value)| !(0.0..=1.0).contains(value))
+            })
+            .map(|(name, _)| name)
+            // stopping above the start speed would flip between walking and standing
+            .or((self.walk_stop_speed > self.walk_start_speed).then_some("walk_stop_speed"))
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, LevelAssetError> {
+        let profile: Self = serde_json::from_slice(bytes)?;
+        match profile.invalid_field() {
+            Some(field) => Err(LevelAssetError::InvalidMovement(field)),
+            None => Ok(profile),
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self, LevelAssetError> {
+        Self::decode(&std::fs::read(path)?)
+    }
+
+    // gravity pulls harder on the way down
+    pub fn gravity_scale(&self, vertical_speed: f32) -> f32 {
+        if vertical_speed > 0.0 {
+            self.rise_gravity_scale
+        } else {
+            self.fall_gravity_scale
+        }
+    }
+
+    // the vertical speed left once jump is no longer held
+    pub fn cut_jump(&self, vertical_speed: f32) -> f32 {
+        vertical_speed.min(self.jump_speed * self.jump_cut)
+    }
+}
+impl Default for MovementProfile {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+#[derive(Default)]
+pub struct MovementProfileLoader;
+impl AssetLoader for MovementProfileLoader {
+    type Asset = MovementProfile;
+    type Error = LevelAssetError;
+    type Settings = ();
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).await?;
+        MovementProfile::decode(&buf)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        const { &[MovementProfile::EXTENSION] }
+    }
+}
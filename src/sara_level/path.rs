@@ -0,0 +1,124 @@
+use bevy::prelude::*;
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Encode, Decode, Serialize, Deserialize)]
+pub enum PathMode {
+    // back to the first waypoint after the last one
+    #[default]
+    Loop,
+    // back along the same waypoints
+    PingPong,
+}
+impl PathMode {
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Loop => Self::PingPong,
+            Self::PingPong => Self::Loop,
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Loop => "loop",
+            Self::PingPong => "ping-pong",
+        }
+    }
+}
+
+// a row of tiles following its waypoints, a waypoint is where the leftmost tile center sits
+#[derive(Debug, Clone, PartialEq, Encode, Decode, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MovingPlatform {
+    pub kind: String,
+    pub atlas_index: usize,
+    pub width: usize,
+    pub waypoints: Vec<(f32, f32)>,
+    pub speed: f32,
+    pub mode: PathMode,
+    // seconds spent on every waypoint
+    pub wait: f32,
+}
+impl MovingPlatform {
+    pub const DEFAULT_WIDTH: usize = 3;
+    pub const DEFAULT_SPEED: f32 = 64.0;
+    pub const DEFAULT_WAIT: f32 = 0.5;
+
+    pub fn new(kind: &str, atlas_index: usize) -> Self {
+        Self {
+            kind: kind.to_string(),
+            atlas_index,
+            width: Self::DEFAULT_WIDTH,
+            waypoints: Vec::new(),
+            speed: Self::DEFAULT_SPEED,
+            mode: PathMode::default(),
+            wait: Self::DEFAULT_WAIT,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.width > 0 && self.waypoints.len() >= 2 && self.speed > 0.0 && self.wait >= 0.0
+    }
+
+    pub fn legs(&self) -> Vec<(Vec2, Vec2)> {
+        let points = self
+            .waypoints
+            .iter()
+            .copied()
+            .map(Vec2::from)
+            .collect::<Vec<_>>();
+        if points.len() < 2 {
+            return Vec::new();
+        }
+        let forward = points.windows(2).map(|pair| (pair[0], pair[1]));
+        match self.mode {
+            PathMode::Loop => forward
+                .chain([(points[points.len() - 1], points[0])])
+                .collect(),
+            PathMode::PingPong => forward
+                .clone()
+                .chain(forward.rev().map(|(from, to)| (to, from)))
+                .collect(),
+        }
+    }
+
+    pub fn period(&self) -> f32 {
+        self.legs()
+            .iter()
+            .map(|(from, to)| self.wait + from.distance(*to) / self.speed)
+            .sum()
+    }
+
+    // waits on the start of every leg, then moves along it at constant speed
+    fn sample(&self, time: f32) -> (Vec2, Vec2) {
+        let start = self.waypoints.first().copied().map(Vec2::from);
+        let period = self.period();
+        if !self.is_valid() || period <= 0.0 {
+            return (start.unwrap_or_default(), Vec2::ZERO);
+        }
+        let mut time = time.rem_euclid(period);
+        for (from, to) in self.legs() {
+            if time < self.wait {
+                return (from, Vec2::ZERO);
+            }
+            time -= self.wait;
+            let duration = from.distance(to) / self.speed;
+            if time < duration {
+                return (
+                    from.lerp(to, time / duration),
+                    (to - from).normalize_or_zero() * self.speed,
+                );
+            }
+            time -= duration;
+        }
+        (start.unwrap_or_default(), Vec2::ZERO)
+    }
+
+    pub fn position_at(&self, time: f32) -> Vec2 {
+        self.sample(time).0
+    }
+
+    pub fn velocity_at(&self, time: f32) -> Vec2 {
+        self.sample(time).1
+    }
+}
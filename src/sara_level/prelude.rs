@@ -0,0 +1,14 @@
+pub use super::LevelFormatPlugin;
+pub use super::ability::*;
+pub use super::asset::*;
+pub use super::chunk::*;
+pub use super::codec::*;
+pub use super::enemy::*;
+pub use super::layer::*;
+pub use super::merge::*;
+pub use super::movement::*;
+pub use super::path::*;
+pub use super::registry::*;
+pub use super::save::*;
+pub use super::tile::*;
+pub use super::validate::*;
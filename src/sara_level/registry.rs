@@ -0,0 +1,221 @@
+use super::asset::{LevelAsset, LevelAssetError};
+use avian2d::prelude::*;
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(PhysicsLayer, Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum GameCollisionLayers {
+    #[default]
+    Default,
+    Enviroment,
+    Operation,
+    Player,
+    Hit,
+    Platform,
+    Enemy,
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TileBehavior {
+    #[default]
+    None,
+    Solid,
+    Damage,
+    Exit,
+    // solid from above only
+    Platform,
+    // solid until the player hits it
+    Breakable,
+}
+
+// points are in tile space with the tile center at the origin, the tile rotation is applied on top
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum TileShape {
+    Rectangle {
+        width: f32,
+        height: f32,
+        #[serde(default)]
+        offset: (f32, f32),
+    },
+    Circle {
+        radius: f32,
+    },
+    Triangle {
+        points: [(f32, f32); 3],
+    },
+    // the convex hull of the points is used
+    Polygon {
+        points: Vec<(f32, f32)>,
+    },
+}
+impl TileShape {
+    pub fn vertices(&self) -> Vec<Vec2> {
+        match self {
+            Self::Rectangle {
+                width,
+                height,
+                offset,
+            } => {
+                let half = Vec2::new(*width, *height) / 2.0;
+                let offset = Vec2::from(*offset);
+                vec![
+                    offset - half,
+                    offset + Vec2::new(half.x, -half.y),
+                    offset + half,
+                    offset + Vec2::new(-half.x, half.y),
+                ]
+            }
+            Self::Circle { .. } => Vec::new(),
+            Self::Triangle { points } => points.iter().copied().map(Vec2::from).collect(),
+            Self::Polygon { points } => points.iter().copied().map(Vec2::from).collect(),
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Self::Rectangle { width, height, .. } => *width > 0.0 && *height > 0.0,
+            Self::Circle { radius } => *radius > 0.0,
+            // a hull needs three points that are not on one line
+            Self::Triangle { .. } | Self::Polygon { .. } => {
+                let points = self.vertices();
+                points.iter().any(|a| {
+                    points.iter().any(|b| {
+                        points
+                            .iter()
+                            .any(|c| (*b - *a).perp_dot(*c - *a).abs() > f32::EPSILON)
+                    })
+                })
+            }
+        }
+    }
+
+    pub fn collider(&self) -> Collider {
+        match self {
+            Self::Rectangle {
+                width,
+                height,
+                offset: (0.0, 0.0),
+            } => Collider::rectangle(*width, *height),
+            Self::Circle { radius } => Collider::circle(*radius),
+            Self::Triangle { points } => Collider::triangle(
+                Vec2::from(points[0]),
+                Vec2::from(points[1]),
+                Vec2::from(points[2]),
+            ),
+            Self::Rectangle { .. } | Self::Polygon { .. } => {
+                Collider::convex_hull(self.vertices()).unwrap()
+            }
+        }
+    }
+}
+
+// a misspelled field is an error, it would otherwise quietly keep its default
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TileDefinition {
+    pub name: String,
+    // first and last atlas frame, the first one is what the palette shows
+    pub frames: (usize, usize),
+    #[serde(default = "TileDefinition::default_frame_time")]
+    pub frame_time: f32,
+    #[serde(default)]
+    pub collider: Option<TileShape>,
+    #[serde(default)]
+    pub layers: Vec<GameCollisionLayers>,
+    #[serde(default)]
+    pub sensor: bool,
+    #[serde(default)]
+    pub behavior: TileBehavior,
+    // HP a damage tile takes from the player per hit
+    #[serde(default = "TileDefinition::default_damage")]
+    pub damage: usize,
+}
+impl TileDefinition {
+    fn default_frame_time() -> f32 {
+        0.15
+    }
+
+    fn default_damage() -> usize {
+        1
+    }
+
+    pub fn is_animated(&self) -> bool {
+        self.frames.0 != self.frames.1
+    }
+
+    pub fn layer_mask(&self) -> LayerMask {
+        self.layers.iter().fold(LayerMask::NONE, |mask, layer| {
+            mask | LayerMask::from(*layer)
+        })
+    }
+}
+
+#[derive(Asset, TypePath, Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TileRegistry {
+    pub tiles: Vec<TileDefinition>,
+}
+impl TileRegistry {
+    pub const ASSET_PATH: &'static str = "data/tiles.def.json";
+    pub const EXTENSION: &'static str = "def.json";
+
+    pub fn file_path() -> String {
+        LevelAsset::ASSET_ROOT.to_string() + Self::ASSET_PATH
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, LevelAssetError> {
+        let registry: Self = serde_json::from_slice(bytes)?;
+        for (index, definition) in registry.tiles.iter().enumerate() {
+            // levels name their tiles, a second definition under the same name could never be used
+            if registry.tiles[..index]
+                .iter()
+                .any(|other| other.name == definition.name)
+            {
+                return Err(LevelAssetError::DuplicateTileName(definition.name.clone()));
+            }
+            if definition
+                .collider
+                .as_ref()
+                .is_some_and(|shape| !shape.is_valid())
+            {
+                return Err(LevelAssetError::InvalidTileShape(definition.name.clone()));
+            }
+        }
+        Ok(registry)
+    }
+
+    pub fn read(path: &Path) -> Result<Self, LevelAssetError> {
+        Self::decode(&std::fs::read(path)?)
+    }
+
+    pub fn get(&self, kind: &str) -> Option<&TileDefinition> {
+        self.tiles.iter().find(|definition| definition.name == kind)
+    }
+}
+
+#[derive(Default)]
+pub struct TileRegistryLoader;
+impl AssetLoader for TileRegistryLoader {
+    type Asset = TileRegistry;
+    type Error = LevelAssetError;
+    type Settings = ();
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut buf = Vec::new();
+        reader.read_to_end(&mut buf).await?;
+        TileRegistry::decode(&buf)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        const { &[TileRegistry::EXTENSION] }
+    }
+}
@@ -0,0 +1,37 @@
+use super::ability::AbilitySet;
+use super::asset::LevelAssetError;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+// progress kept between runs, a field missing from an older file starts empty
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveData {
+    pub abilities: AbilitySet,
+}
+impl SaveData {
+    pub const FILE_PATH: &'static str = "sara.save.json";
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, LevelAssetError> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+
+    pub fn encode(&self) -> Result<Vec<u8>, LevelAssetError> {
+        let mut buf = serde_json::to_vec_pretty(self)?;
+        buf.push(b'\n');
+        Ok(buf)
+    }
+
+    // no file yet is a fresh save
+    pub fn read(path: &Path) -> Result<Self, LevelAssetError> {
+        match std::fs::read(path) {
+            Ok(bytes) => Self::decode(&bytes),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), LevelAssetError> {
+        Ok(std::fs::write(path, self.encode()?)?)
+    }
+}
@@ -0,0 +1,47 @@
+use super::registry::TileDefinition;
+use bevy::prelude::*;
+use bincode::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+
+pub struct TileAtlas;
+impl TileAtlas {
+    pub const TEXTURE_PATH: &'static str = "images/building/tiles.png";
+    pub const TILE_SIZE: UVec2 = UVec2::new(32, 32);
+    pub const ROWS: u32 = 16;
+    pub const COLS: u32 = 16;
+    pub const LEN: usize = (Self::ROWS * Self::COLS) as usize;
+
+    pub fn layout() -> TextureAtlasLayout {
+        TextureAtlasLayout::from_grid(Self::TILE_SIZE, Self::COLS, Self::ROWS, None, None)
+    }
+
+    pub fn cell(pos: (f32, f32)) -> IVec2 {
+        (Vec2::from(pos) / Self::TILE_SIZE.as_vec2())
+            .floor()
+            .as_ivec2()
+    }
+}
+
+// `kind` names a `TileDefinition` in the `TileRegistry`
+#[derive(Debug, Clone, PartialEq, Encode, Decode, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TileDescriptor {
+    pub tile_pos: (f32, f32),
+    pub kind: String,
+    pub rotation: f32,
+    pub atlas_index: usize,
+}
+impl TileDescriptor {
+    pub fn new(tile_pos: (f32, f32), definition: &TileDefinition, rotation: f32) -> Self {
+        Self {
+            tile_pos,
+            kind: definition.name.clone(),
+            rotation,
+            atlas_index: definition.frames.0,
+        }
+    }
+
+    pub fn cell(&self) -> IVec2 {
+        TileAtlas::cell(self.tile_pos)
+    }
+}
@@ -0,0 +1,344 @@
+use super::ability::Ability;
+use super::asset::{LevelAsset, LevelAssetError};
+use super::layer::TileLayer;
+use super::registry::{TileBehavior, TileRegistry};
+use super::tile::{TileAtlas, TileDescriptor};
+use bevy::prelude::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum LevelIssue {
+    #[error("{kind} tile at cell {cell} is outside the {rows}x{cols} grid")]
+    OutOfBounds {
+        cell: IVec2,
+        kind: String,
+        rows: usize,
+        cols: usize,
+    },
+    #[error("tile at cell {cell} has kind {kind}, which is not in the tile registry")]
+    UnknownKind { cell: IVec2, kind: String },
+    #[error("atlas index {index} at cell {cell} is past the {len} atlas tiles", len = TileAtlas::LEN)]
+    AtlasIndexOutOfRange { cell: IVec2, index: usize },
+    #[error("{count} tiles share cell {cell} on layer {layer}")]
+    DuplicatePosition {
+        layer: String,
+        cell: IVec2,
+        count: usize,
+    },
+    #[error("layer name {0} is used more than once")]
+    DuplicateLayer(String),
+    #[error("layer {layer} has colliders, its parallax {parallax} must be 1.0")]
+    ColliderParallax { layer: String, parallax: f32 },
+    #[error("no exit tile on a collision layer, the level can not be finished")]
+    MissingExit,
+    #[error("entry {0:?} is inside a wall")]
+    EntryInWall((f32, f32)),
+    #[error("next level {0} has no level file")]
+    MissingNext(usize),
+    #[error("moving platform {index} has kind {kind}, which is not in the tile registry")]
+    UnknownPlatformKind { index: usize, kind: String },
+    #[error(
+        "moving platform {0} needs a width, two waypoints, a positive speed and no negative wait"
+    )]
+    InvalidPlatformPath(usize),
+    #[error("{kind} {index} spawns at cell {cell}, outside the grid")]
+    EnemyOutOfBounds {
+        index: usize,
+        kind: &'static str,
+        cell: IVec2,
+    },
+    #[error("{kind} {index} needs a positive speed and range")]
+    InvalidEnemy { index: usize, kind: &'static str },
+    #[error("checkpoint {index} is at cell {cell}, outside the grid")]
+    CheckpointOutOfBounds { index: usize, cell: IVec2 },
+    #[error("checkpoint {0} is inside a wall")]
+    CheckpointInWall(usize),
+    #[error("{ability} pickup {index} is at cell {cell}, outside the grid", ability = ability.name())]
+    PickupOutOfBounds {
+        index: usize,
+        ability: Ability,
+        cell: IVec2,
+    },
+    #[error("pickup {0} is inside a wall")]
+    PickupInWall(usize),
+    #[error("kill depth {0} must be a finite distance below the grid")]
+    InvalidKillDepth(f32),
+    #[error("gravity {0} must be a finite positive pull")]
+    InvalidGravity(f32),
+}
+
+impl LevelAsset {
+    pub fn contains_cell(&self, cell: IVec2) -> bool {
+        cell.x >= 0 && cell.y >= 0 && (cell.x as usize) < self.cols && (cell.y as usize) < self.rows
+    }
+
+    // the grid in world space, cell (0, 0) sits on the origin
+    pub fn bounds(&self) -> Rect {
+        Rect::from_corners(
+            Vec2::ZERO,
+            Vec2::new(self.cols as f32, self.rows as f32) * TileAtlas::TILE_SIZE.as_vec2(),
+        )
+    }
+
+    // anything below this height has fallen out of the level
+    pub fn kill_plane(&self) -> f32 {
+        self.bounds().min.y - self.kill_depth
+    }
+
+    fn has_behavior(
+        registry: &TileRegistry,
+        descriptor: &TileDescriptor,
+        behavior: TileBehavior,
+    ) -> bool {
+        registry
+            .get(&descriptor.kind)
+            .is_some_and(|definition| definition.behavior == behavior)
+    }
+
+    fn is_in_wall(&self, registry: &TileRegistry, position: (f32, f32)) -> bool {
+        let cell = TileAtlas::cell(position);
+        self.collision_tiles().any(|descriptor| {
+            descriptor.cell() == cell
+                && (Self::has_behavior(registry, descriptor, TileBehavior::Solid)
+                    || Self::has_behavior(registry, descriptor, TileBehavior::Breakable))
+        })
+    }
+
+    // `dir` is where the level file lives, `next` is looked up next to it.
+    pub fn validate(&self, dir: &Path, registry: &TileRegistry) -> Vec<LevelIssue> {
+        let mut issues = Vec::new();
+        for descriptor in self.tiles() {
+            let cell = descriptor.cell();
+            if !self.contains_cell(cell) {
+                issues.push(LevelIssue::OutOfBounds {
+                    cell,
+                    kind: descriptor.kind.clone(),
+                    rows: self.rows,
+                    cols: self.cols,
+                });
+            }
+            if registry.get(&descriptor.kind).is_none() {
+                issues.push(LevelIssue::UnknownKind {
+                    cell,
+                    kind: descriptor.kind.clone(),
+                });
+            }
+            if descriptor.atlas_index >= TileAtlas::LEN {
+                issues.push(LevelIssue::AtlasIndexOutOfRange {
+                    cell,
+                    index: descriptor.atlas_index,
+                });
+            }
+        }
+
+        let mut names = BTreeSet::new();
+        for layer in &self.layers {
+            if !names.insert(&layer.name) {
+                issues.push(LevelIssue::DuplicateLayer(layer.name.clone()));
+            }
+            // only the sprites follow the parallax, the bodies would stay where the world puts them
+            if (layer.collider || layer.name == TileLayer::COLLISION) && layer.parallax != 1.0 {
+                issues.push(LevelIssue::ColliderParallax {
+                    layer: layer.name.clone(),
+                    parallax: layer.parallax,
+                });
+            }
+            let mut cells = HashMap::<IVec2, usize>::new();
+            for descriptor in &layer.data {
+                *cells.entry(descriptor.cell()).or_default() += 1;
+            }
+            let mut duplicates = cells
+                .into_iter()
+                .filter(|(_, count)| *count > 1)
+                .collect::<Vec<_>>();
+            duplicates.sort_by_key(|(cell, _)| (cell.y, cell.x));
+            issues.extend(duplicates.into_iter().map(|(cell, count)| {
+                LevelIssue::DuplicatePosition {
+                    layer: layer.name.clone(),
+                    cell,
+                    count,
+                }
+            }));
+        }
+
+        if !self
+            .collision_tiles()
+            .any(|descriptor| Self::has_behavior(registry, descriptor, TileBehavior::Exit))
+        {
+            issues.push(LevelIssue::MissingExit);
+        }
+
+        if self.is_in_wall(registry, self.entry) {
+            issues.push(LevelIssue::EntryInWall(self.entry));
+        }
+
+        for (index, platform) in self.platforms.iter().enumerate() {
+            if registry.get(&platform.kind).is_none() {
+                issues.push(LevelIssue::UnknownPlatformKind {
+                    index,
+                    kind: platform.kind.clone(),
+                });
+            }
+            if !platform.is_valid() {
+                issues.push(LevelIssue::InvalidPlatformPath(index));
+            }
+        }
+
+        for (index, enemy) in self.enemies.iter().enumerate() {
+            let kind = enemy.kind.name();
+            let cell = TileAtlas::cell(enemy.position);
+            if !self.contains_cell(cell) {
+                issues.push(LevelIssue::EnemyOutOfBounds { index, kind, cell });
+            }
+            if !enemy.is_valid() {
+                issues.push(LevelIssue::InvalidEnemy { index, kind });
+            }
+        }
+
+        for (index, checkpoint) in self.checkpoints.iter().enumerate() {
+            let cell = TileAtlas::cell(*checkpoint);
+            if !self.contains_cell(cell) {
+                issues.push(LevelIssue::CheckpointOutOfBounds { index, cell });
+            }
+            if self.is_in_wall(registry, *checkpoint) {
+                issues.push(LevelIssue::CheckpointInWall(index));
+            }
+        }
+
+        for (index, pickup) in self.pickups.iter().enumerate() {
+            let cell = TileAtlas::cell(pickup.position);
+            if !self.contains_cell(cell) {
+                issues.push(LevelIssue::PickupOutOfBounds {
+                    index,
+                    ability: pickup.ability,
+                    cell,
+                });
+            }
+            if self.is_in_wall(registry, pickup.position) {
+                issues.push(LevelIssue::PickupInWall(index));
+            }
+        }
+
+        if !self.kill_depth.is_finite() || self.kill_depth < 0.0 {
+            issues.push(LevelIssue::InvalidKillDepth(self.kill_depth));
+        }
+
+        if let Some(gravity) = self.gravity
+            && !(gravity.is_finite() && gravity > 0.0)
+        {
+            issues.push(LevelIssue::InvalidGravity(gravity));
+        }
+
+        if let Some(next) = self.next
+            && LevelAsset::locate_in(dir, next).is_none()
+        {
+            issues.push(LevelIssue::MissingNext(next));
+        }
+        issues
+    }
+}
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ChainIssue {
+    #[error("first level {0} has no level file")]
+    MissingFirst(usize),
+    #[error("level {from} points at level {to}, which has no level file")]
+    DanglingNext { from: usize, to: usize },
+    #[error("levels {0:?} form a cycle")]
+    Cycle(Vec<usize>),
+    #[error("level {0} can not be reached from level {first}", first = LevelAsset::FIRST)]
+    Unreachable(usize),
+}
+
+pub struct LevelPack {
+    pub levels: BTreeMap<usize, LevelAsset>,
+}
+impl LevelPack {
+    pub fn load(dir: &Path) -> Result<Self, LevelAssetError> {
+        let mut ids = BTreeSet::new();
+        for entry in std::fs::read_dir(dir)? {
+            if let Some((id, _)) =
+                LevelAsset::parse_file_name(&entry?.file_name().to_string_lossy())
+            {
+                ids.insert(id);
+            }
+        }
+        let mut levels = BTreeMap::new();
+        for id in ids {
+            // same preference as the game when both formats exist
+            let format = LevelAsset::locate_in(dir, id).unwrap();
+            let bytes = std::fs::read(dir.join(LevelAsset::file_name(id, format)))?;
+            levels.insert(id, LevelAsset::decode(&bytes, format)?);
+        }
+        Ok(Self { levels })
+    }
+
+    pub fn next(&self, id: usize) -> Option<usize> {
+        self.levels.get(&id).and_then(|level| level.next)
+    }
+
+    fn cycles(&self) -> Vec<Vec<usize>> {
+        let mut cycles = Vec::new();
+        let mut done = BTreeSet::new();
+        for &start in self.levels.keys() {
+            let mut path = Vec::new();
+            let mut current = Some(start);
+            while let Some(id) = current {
+                if done.contains(&id) || !self.levels.contains_key(&id) {
+                    break;
+                }
+                if let Some(index) = path.iter().position(|&visited| visited == id) {
+                    let mut cycle = path[index..].to_vec();
+                    let min = cycle
+                        .iter()
+                        .enumerate()
+                        .min_by_key(|(_, id)| **id)
+                        .unwrap()
+                        .0;
+                    cycle.rotate_left(min);
+                    cycles.push(cycle);
+                    break;
+                }
+                path.push(id);
+                current = self.next(id);
+            }
+            done.extend(path);
+        }
+        cycles
+    }
+
+    pub fn validate_chain(&self) -> Vec<ChainIssue> {
+        let mut issues = Vec::new();
+        if !self.levels.contains_key(&LevelAsset::FIRST) {
+            issues.push(ChainIssue::MissingFirst(LevelAsset::FIRST));
+        }
+
+        for (&from, level) in &self.levels {
+            if let Some(to) = level.next
+                && !self.levels.contains_key(&to)
+            {
+                issues.push(ChainIssue::DanglingNext { from, to });
+            }
+        }
+
+        issues.extend(self.cycles().into_iter().map(ChainIssue::Cycle));
+
+        let mut reachable = BTreeSet::new();
+        let mut current = Some(LevelAsset::FIRST);
+        while let Some(id) = current {
+            if !self.levels.contains_key(&id) || !reachable.insert(id) {
+                break;
+            }
+            current = self.next(id);
+        }
+        issues.extend(
+            self.levels
+                .keys()
+                .filter(|id| !reachable.contains(id))
+                .map(|id| ChainIssue::Unreachable(*id)),
+        );
+        issues
+    }
+}
@@ -5,18 +5,26 @@ use crate::AppState;
 use bevy::prelude::*;
 
 pub struct Tile;
+impl Tile {
+    fn prepare(mut command: Commands, asset_server: Res<AssetServer>) {
+        command.insert_resource(data::LevelStaticResource::new(&asset_server));
+    }
+
+    // the palette is built from the tile definitions, so they must be loaded first
+    fn ready(
+        asset_server: Res<AssetServer>,
+        level_resource: Res<data::LevelStaticResource>,
+        mut next_state: ResMut<NextState<AppState>>,
+    ) {
+        if asset_server.is_loaded(&level_resource.tiles_handle) {
+            next_state.set(AppState::Running);
+        }
+    }
+}
 impl Plugin for Tile {
     fn build(&self, app: &mut bevy::app::App) {
-        app.init_asset::<data::LevelAsset>()
-            .init_asset_loader::<data::LevelAssetLoader>()
-            .add_systems(
-                OnEnter(AppState::Prepare),
-                |mut command: Commands,
-                 asset_server: Res<AssetServer>,
-                 mut next_state: ResMut<NextState<AppState>>| {
-                    command.insert_resource(data::LevelStaticResource::new(&asset_server));
-                    next_state.set(AppState::Running);
-                },
-            );
+        app.add_plugins(data::LevelFormatPlugin)
+            .add_systems(OnEnter(AppState::Prepare), Self::prepare)
+            .add_systems(Update, Self::ready.run_if(in_state(AppState::Prepare)));
     }
 }
@@ -0,0 +1,45 @@
+use super::*;
+use crate::utils::aseprite::*;
+use bevy::prelude::*;
+
+#[derive(Bundle)]
+pub struct TileSprite(Aseprite, Transform);
+impl TileSprite {
+    const STILL_FRAME_TIME: f32 = 0.15;
+
+    pub fn new(
+        translation: Vec3,
+        rotation: f32,
+        atlas_index: usize,
+        definition: Option<&TileDefinition>,
+        level_resource: &Res<LevelStaticResource>,
+    ) -> Self {
+        // only a tile drawn with its kind's first frame plays the kind's animation
+        let (indices, frame_time) = match definition {
+            Some(definition) if definition.is_animated() && definition.frames.0 == atlas_index => (
+                AsepriteIndices::new(definition.frames.0, definition.frames.1),
+                definition.frame_time,
+            ),
+            _ => (
+                AsepriteIndices::new(atlas_index, atlas_index),
+                Self::STILL_FRAME_TIME,
+            ),
+        };
+        Self(
+            Aseprite::new(
+                Sprite {
+                    image: level_resource.texture_handle.clone(),
+                    texture_atlas: Some(TextureAtlas {
+                        layout: level_resource.layout_handle.clone(),
+                        index: atlas_index,
+                    }),
+                    ..Default::default()
+                },
+                indices,
+                AsepritePlaying(true),
+                AsepriteTimer(Timer::from_seconds(frame_time, TimerMode::Repeating)),
+            ),
+            Transform::from_translation(translation).with_rotation(Quat::from_rotation_z(rotation)),
+        )
+    }
+}
@@ -20,14 +20,82 @@ enum UIState {
 #[derive(Resource)]
 struct Selected {
     id: Entity, // selected id
-    typ: TileType,
+    kind: String,
     rotation: f32,
+    atlas_index: usize,
 }
 
 struct TileData {
     id: Entity, //tile id
-    typ: TileType,
+    kind: String,
     rotation: f32,
+    atlas_index: usize,
+}
+
+struct MapLayer {
+    name: String,
+    z: f32,
+    parallax: f32,
+    collider: bool,
+    visible: bool,
+    data: HashMap<UVec2, TileData>,
+}
+impl MapLayer {
+    fn new(layer: &TileLayer) -> Self {
+        Self {
+            name: layer.name.clone(),
+            z: layer.z,
+            parallax: layer.parallax,
+            collider: layer.collider,
+            visible: true,
+            data: HashMap::new(),
+        }
+    }
+
+    fn visibility(&self) -> Visibility {
+        if self.visible {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        }
+    }
+
+    fn to_tile_layer(&self) -> TileLayer {
+        let mut data = Vec::new();
+        for (key, tile_data) in &self.data {
+            data.push(TileDescriptor {
+                tile_pos: (key.x as f32, key.y as f32),
+                kind: tile_data.kind.clone(),
+                rotation: tile_data.rotation,
+                atlas_index: tile_data.atlas_index,
+            });
+        }
+        // HashMap order is random, keep saved files stable so they diff cleanly
+        data.sort_by(|a, b| {
+            (a.tile_pos.1, a.tile_pos.0)
+                .partial_cmp(&(b.tile_pos.1, b.tile_pos.0))
+                .unwrap()
+        });
+        TileLayer::new(&self.name, self.z, self.parallax, self.collider).with_data(data)
+    }
+}
+
+// applied to the edited path and to every new one
+struct PathSettings {
+    width: usize,
+    speed: f32,
+    wait: f32,
+    mode: PathMode,
+}
+impl Default for PathSettings {
+    fn default() -> Self {
+        Self {
+            width: MovingPlatform::DEFAULT_WIDTH,
+            speed: MovingPlatform::DEFAULT_SPEED,
+            wait: MovingPlatform::DEFAULT_WAIT,
+            mode: PathMode::default(),
+        }
+    }
 }
 
 #[derive(Resource, Default)]
@@ -35,9 +103,99 @@ struct MapData {
     id: usize,
     rows: usize,
     cols: usize,
-    data: HashMap<UVec2, TileData>,
+    layers: Vec<MapLayer>,
+    layer: usize, // edited layer
     entry: (f32, f32),
     next: Option<usize>,
+    format: LevelFormat,
+    platforms: Vec<MovingPlatform>,
+    path: Option<usize>, // edited platform path
+    path_settings: PathSettings,
+    enemies: Vec<EnemySpawn>,
+    enemy: EnemyKind, // placed by the next click
+    spawn: Option<usize>, // edited enemy
+    checkpoints: Vec<(f32, f32)>,
+    kill_depth: f32,
+    side_walls: bool,
+    abilities: AbilitySet,
+    pickups: Vec<AbilityPickup>,
+    pickup: Ability, // placed by the next click
+    gravity: Option<f32>,
+}
+impl MapData {
+    fn reset_layers(&mut self, layers: &[TileLayer]) {
+        let hidden = self
+            .layers
+            .iter()
+            .filter(|layer| !layer.visible)
+            .map(|layer| layer.name.clone())
+            .collect::<Vec<_>>();
+        self.layers = layers.iter().map(MapLayer::new).collect();
+        if self.layers.is_empty() {
+            self.layers = TileLayer::defaults().iter().map(MapLayer::new).collect();
+        }
+        for layer in &mut self.layers {
+            layer.visible = !hidden.contains(&layer.name);
+        }
+        if self.layer >= self.layers.len() {
+            self.layer = self
+                .layers
+                .iter()
+                .position(|layer| layer.collider)
+                .unwrap_or_default();
+        }
+    }
+
+    fn current(&self) -> &MapLayer {
+        &self.layers[self.layer]
+    }
+
+    fn current_mut(&mut self) -> &mut MapLayer {
+        let layer = self.layer;
+        &mut self.layers[layer]
+    }
+
+    fn start_path(&mut self, selected: &Selected) {
+        self.platforms.push(MovingPlatform {
+            width: self.path_settings.width,
+            speed: self.path_settings.speed,
+            wait: self.path_settings.wait,
+            mode: self.path_settings.mode,
+            ..MovingPlatform::new(&selected.kind, selected.atlas_index)
+        });
+        self.path = Some(self.platforms.len() - 1);
+    }
+
+    fn current_path_mut(&mut self) -> Option<&mut MovingPlatform> {
+        self.path.and_then(|path| self.platforms.get_mut(path))
+    }
+
+    fn apply_path_settings(&mut self) {
+        let settings = &self.path_settings;
+        let (width, speed, wait, mode) =
+            (settings.width, settings.speed, settings.wait, settings.mode);
+        if let Some(platform) = self.current_path_mut() {
+            platform.width = width;
+            platform.speed = speed;
+            platform.wait = wait;
+            platform.mode = mode;
+        }
+    }
+
+    // the edited enemy takes the parameters of the next one
+    fn apply_enemy(&mut self) {
+        let kind = self.enemy;
+        if let Some(spawn) = self.spawn.and_then(|spawn| self.enemies.get_mut(spawn)) {
+            spawn.kind = kind;
+        }
+    }
+
+    // paths left without a waypoint are dropped once editing moves on
+    fn finish_path(&mut self) {
+        self.path = None;
+        self.platforms
+            .retain(|platform| !platform.waypoints.is_empty());
+    }
 }
 
 #[derive(SubStates, PartialEq, Eq, Clone, Copy, Default, Debug, Hash)]
@@ -46,6 +204,10 @@ enum EditorState {
     #[default]
     Tracking,
     Selected,
+    Path,
+    Enemy,
+    Checkpoint,
+    Pickup,
 }
 
 #[derive(Component)]
@@ -87,15 +249,26 @@ impl UIPlugin {
                 map_data.id = id;
                 command.entity(entity).despawn();
                 command.entity(editor.0).despawn();
-                if !std::fs::exists(&LevelDynamicResource::data_path(id))? {
+                if let Some(format) = LevelAsset::locate(id) {
+                    map_data.format = format;
+                    next_state.set(UIState::Loading);
+                } else {
                     map_data.cols = 0;
                     map_data.rows = 0;
                     map_data.next = None;
+                    map_data.platforms.clear();
+                    map_data.enemies.clear();
+                    map_data.spawn = None;
+                    map_data.checkpoints.clear();
+                    map_data.kill_depth = LevelAsset::DEFAULT_KILL_DEPTH;
+                    map_data.side_walls = true;
+                    map_data.abilities = AbilitySet::default();
+                    map_data.pickups.clear();
+                    map_data.gravity = None;
+                    map_data.reset_layers(&TileLayer::defaults());
                     command.trigger(ParseTilesEvent);
                     command.trigger(UpdateEditLine);
                     next_state.set(UIState::Running);
-                } else {
-                    next_state.set(UIState::Loading);
                 }
                 Ok(())
             },
@@ -103,7 +276,11 @@ impl UIPlugin {
     }
 
     fn load(mut command: Commands, asset_server: Res<AssetServer>, map_data: Res<MapData>) {
-        command.insert_resource(LevelDynamicResource::new(map_data.id, &asset_server));
+        command.insert_resource(LevelDynamicResource::new(
+            map_data.id,
+            map_data.format,
+            &asset_server,
+        ));
     }
 
     fn ready(
@@ -120,6 +297,15 @@ impl UIPlugin {
             map_data.cols = level_asset.cols;
             map_data.entry = level_asset.entry;
             map_data.next = level_asset.next;
+            map_data.platforms = level_asset.platforms.clone();
+            map_data.enemies = level_asset.enemies.clone();
+            map_data.spawn = None;
+            map_data.checkpoints = level_asset.checkpoints.clone();
+            map_data.kill_depth = level_asset.kill_depth;
+            map_data.side_walls = level_asset.side_walls;
+            map_data.abilities = level_asset.abilities.clone();
+            map_data.pickups = level_asset.pickups.clone();
+            map_data.gravity = level_asset.gravity;
             command.trigger(ParseTilesEvent);
             command.trigger(UpdateEditLine);
             next_state.set(UIState::Running);
@@ -152,27 +338,31 @@ impl UIPlugin {
                 map_data.id = id;
                 command.entity(entity).despawn();
                 command.entity(editor.0).despawn();
-                let mut data = Vec::new();
-                for key in map_data.data.keys() {
-                    let tile_data = map_data.data.get(key).unwrap();
-                    data.push(TileDescriptor {
-                        tile_typ: tile_data.typ,
-                        tile_pos: (key.x as f32, key.y as f32),
-                        rotation: tile_data.rotation,
-                    });
-                }
                 let map = LevelAsset {
                     rows: map_data.rows,
                     cols: map_data.cols,
-                    data,
+                    layers: map_data
+                        .layers
+                        .iter()
+                        .map(MapLayer::to_tile_layer)
+                        .collect(),
                     entry: map_data.entry,
                     next: map_data.next,
+                    platforms: map_data.platforms.clone(),
+                    enemies: map_data.enemies.clone(),
+                    checkpoints: map_data.checkpoints.clone(),
+                    kill_depth: map_data.kill_depth,
+                    side_walls: map_data.side_walls,
+                    abilities: map_data.abilities.clone(),
+                    pickups: map_data.pickups.clone(),
+                    gravity: map_data.gravity,
                 };
-                std::fs::write(
-                    &LevelDynamicResource::data_path(map_data.id),
-                    &bincode::encode_to_vec(&map, bincode::config::standard())?,
-                )?;
-                Ok(next_state.set(UIState::Running))
+                map.write(map_data.id, map_data.format)?;
+                next_state.set(UIState::Running);
+                for issue in LevelPack::load(&LevelAsset::data_dir())?.validate_chain() {
+                    warn!("level chain: {issue}");
+                }
+                Ok(())
             },
         );
     }
@@ -20,16 +20,20 @@ pub struct GridCreateEvent;
 #[derive(Event)]
 pub struct ParseTilesEvent;
 
-#[derive(Component)]
-#[require(Sprite)]
-struct CurrentTile;
-
 pub(super) struct TilesPlugin;
 impl TilesPlugin {
     const TILE_SIZE: f32 = 32.0;
     const SPACING: f32 = Self::TILE_SIZE;
     const COLOR_VERTEX: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
     const TILEMAP_SCALE_RANGE: (f32, f32) = (0.65, 1.5);
+    const PATH_COLOR: Color = Color::srgb(0.0, 1.0, 1.0);
+    const EDITED_PATH_COLOR: Color = Color::srgb(1.0, 1.0, 0.0);
+    const WOLF_COLOR: Color = Color::srgb(1.0, 0.5, 0.0);
+    const BAT_COLOR: Color = Color::srgb(1.0, 0.0, 1.0);
+    const EDITED_ENEMY_COLOR: Color = Color::srgb(1.0, 1.0, 0.0);
+    const CHECKPOINT_COLOR: Color = Color::srgb(0.0, 1.0, 0.0);
+    const PICKUP_COLOR: Color = Color::srgb(1.0, 1.0, 1.0);
+    const BOUNDS_COLOR: Color = Color::srgb(1.0, 0.0, 0.0);
 
     fn init(window: Single<&Window>, mut command: Commands) {
         command.spawn((
@@ -103,13 +107,16 @@ impl TilesPlugin {
         mut command: Commands,
         mut map_data: ResMut<MapData>,
         asset: Res<Assets<LevelAsset>>,
+        tile_registry: Res<Assets<TileRegistry>>,
         level_static_resource: Res<LevelStaticResource>,
         level_dynamic_resource: Res<LevelDynamicResource>,
     ) {
-        for tile_data in map_data.data.values() {
-            command.entity(tile_data.id).despawn();
+        for layer in &mut map_data.layers {
+            for tile_data in layer.data.values() {
+                command.entity(tile_data.id).despawn();
+            }
+            layer.data.clear();
         }
-        map_data.data.clear();
         command.trigger(GridCreateEvent);
 
         if map_data.cols == 0 || map_data.rows == 0 {
@@ -122,45 +129,62 @@ impl TilesPlugin {
         );
 
         let level_asset = asset.get(&level_dynamic_resource.0).unwrap();
-        for descriptor in &level_asset.data {
-            if descriptor.tile_pos.0 > limit_x || descriptor.tile_pos.1 > limit_y {
-                continue;
+        let registry = tile_registry
+            .get(&level_static_resource.tiles_handle)
+            .unwrap();
+        map_data.reset_layers(&level_asset.layers);
+        for (index, tile_layer) in level_asset.layers.iter().enumerate() {
+            let layer = &mut map_data.layers[index];
+            for descriptor in &tile_layer.data {
+                if descriptor.tile_pos.0 > limit_x || descriptor.tile_pos.1 > limit_y {
+                    continue;
+                }
+                let translation = Vec3::new(descriptor.tile_pos.0, descriptor.tile_pos.1, layer.z);
+                let id = Self::spawn_tile(
+                    &mut command,
+                    registry.get(&descriptor.kind),
+                    translation,
+                    descriptor.rotation,
+                    descriptor.atlas_index,
+                    layer.visibility(),
+                    &level_static_resource,
+                );
+                layer.data.insert(
+                    translation.truncate().as_uvec2(),
+                    TileData {
+                        id,
+                        kind: descriptor.kind.clone(),
+                        rotation: descriptor.rotation,
+                        atlas_index: descriptor.atlas_index,
+                    },
+                );
             }
-            let translation = Vec3::new(descriptor.tile_pos.0, descriptor.tile_pos.1, 0.0);
-            let id = match descriptor.tile_typ {
-                TileType::Pass => command
-                    .spawn(PassBox::new(
-                        translation,
-                        descriptor.rotation,
-                        &level_static_resource,
-                    ))
-                    .id(),
-                TileType::Wall => command
-                    .spawn(Floor::new(
-                        translation,
-                        descriptor.rotation,
-                        &level_static_resource,
-                    ))
-                    .id(),
-                TileType::Trap => command
-                    .spawn(HitBox::new(
-                        translation,
-                        descriptor.rotation,
-                        &level_static_resource,
-                    ))
-                    .id(),
-            };
-            map_data.data.insert(
-                translation.truncate().as_uvec2(),
-                TileData {
-                    id,
-                    typ: descriptor.tile_typ,
-                    rotation: descriptor.rotation,
-                },
-            );
         }
     }
 
+    fn spawn_tile(
+        command: &mut Commands,
+        definition: Option<&TileDefinition>,
+        translation: Vec3,
+        rotation: f32,
+        atlas_index: usize,
+        visibility: Visibility,
+        level_static_resource: &Res<LevelStaticResource>,
+    ) -> Entity {
+        command
+            .spawn((
+                TileSprite::new(
+                    translation,
+                    rotation,
+                    atlas_index,
+                    definition,
+                    level_static_resource,
+                ),
+                visibility,
+            ))
+            .id()
+    }
+
     //fix a bevy's bug
     fn resize(
         window: Single<&Window>,
@@ -250,12 +274,14 @@ impl TilesPlugin {
         ))
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn selected(
         mut command: Commands,
         window: Single<&Window>,
         selected: Res<Selected>,
         mut map_data: ResMut<MapData>,
         mouse_buttons: Res<ButtonInput<MouseButton>>,
+        tile_registry: Res<Assets<TileRegistry>>,
         level_static_resource: Res<LevelStaticResource>,
         camera_transform: Single<&Transform, With<TilesMarker>>,
         camera_projection: Single<&Projection, With<TilesMarker>>,
@@ -284,45 +310,42 @@ impl TilesPlugin {
         }
 
         let key = real_translation.truncate().as_uvec2();
+        let layer = map_data.current_mut();
+        // hidden layers are locked so nothing changes out of sight
+        if !layer.visible {
+            return;
+        }
 
-        if let Some(tile_data) = map_data.data.get(&key) {
-            if selected.typ == tile_data.typ && selected.rotation == tile_data.rotation {
+        if let Some(tile_data) = layer.data.get(&key) {
+            if selected.kind == tile_data.kind
+                && selected.rotation == tile_data.rotation
+                && selected.atlas_index == tile_data.atlas_index
+            {
                 return;
             } else {
                 command.entity(tile_data.id).despawn();
-                map_data.data.remove(&key);
+                layer.data.remove(&key);
             }
         }
 
-        let id = match selected.typ {
-            TileType::Wall => command
-                .spawn(Floor::new(
-                    real_translation,
-                    selected.rotation,
-                    &level_static_resource,
-                ))
-                .id(),
-            TileType::Pass => command
-                .spawn(PassBox::new(
-                    real_translation,
-                    selected.rotation,
-                    &level_static_resource,
-                ))
-                .id(),
-            TileType::Trap => command
-                .spawn(HitBox::new(
-                    real_translation,
-                    selected.rotation,
-                    &level_static_resource,
-                ))
-                .id(),
-        };
-        map_data.data.insert(
-            real_translation.truncate().as_uvec2(),
+        let id = Self::spawn_tile(
+            &mut command,
+            tile_registry
+                .get(&level_static_resource.tiles_handle)
+                .and_then(|registry| registry.get(&selected.kind)),
+            real_translation.with_z(layer.z),
+            selected.rotation,
+            selected.atlas_index,
+            layer.visibility(),
+            &level_static_resource,
+        );
+        layer.data.insert(
+            key,
             TileData {
                 id,
-                typ: selected.typ,
+                kind: selected.kind.clone(),
                 rotation: selected.rotation,
+                atlas_index: selected.atlas_index,
             },
         );
     }
@@ -335,17 +358,233 @@ impl TilesPlugin {
         camera_transform: Single<&Transform, With<TilesMarker>>,
         camera_projection: Single<&Projection, With<TilesMarker>>,
     ) {
-        if !mouse_buttons.pressed(MouseButton::Right) || mouse_buttons.get_pressed().len() != 1 {
+        if !mouse_buttons.pressed(MouseButton::Right)
+            || mouse_buttons.get_pressed().len() != 1
+            || !map_data.current().visible
+        {
             return;
         }
         if let Some(real_translation) =
             Self::get_real_translation(window, camera_transform, camera_projection)
-        {
-            if let Some(tile_data) = map_data
+            && let Some(tile_data) = map_data
+                .current_mut()
                 .data
                 .remove(&real_translation.truncate().as_uvec2())
-            {
-                command.entity(tile_data.id).despawn();
+        {
+            command.entity(tile_data.id).despawn();
+        }
+    }
+
+    // left click adds a waypoint to the edited path, right click takes the last one back
+    fn place_waypoint(
+        window: Single<&Window>,
+        mut map_data: ResMut<MapData>,
+        mouse_buttons: Res<ButtonInput<MouseButton>>,
+        camera_transform: Single<&Transform, With<TilesMarker>>,
+        camera_projection: Single<&Projection, With<TilesMarker>>,
+    ) {
+        let Some(real_translation) =
+            Self::get_real_translation(window, camera_transform, camera_projection)
+        else {
+            return;
+        };
+        let Some(platform) = map_data.current_path_mut() else {
+            return;
+        };
+        if mouse_buttons.just_pressed(MouseButton::Left) {
+            platform
+                .waypoints
+                .push((real_translation.x, real_translation.y));
+        } else if mouse_buttons.just_pressed(MouseButton::Right) {
+            platform.waypoints.pop();
+        }
+    }
+
+    fn draw_paths(map_data: Res<MapData>, mut gizmos: Gizmos) {
+        for (index, platform) in map_data.platforms.iter().enumerate() {
+            let color = if map_data.path == Some(index) {
+                Self::EDITED_PATH_COLOR
+            } else {
+                Self::PATH_COLOR
+            };
+            for (from, to) in platform.legs() {
+                gizmos.line_2d(from, to, color);
+            }
+            for waypoint in &platform.waypoints {
+                gizmos.circle_2d(Vec2::from(*waypoint), Self::TILE_SIZE / 4.0, color);
+            }
+            // the platform footprint on its first waypoint
+            if let Some(first) = platform.waypoints.first() {
+                let size = Vec2::new(platform.width as f32, 1.0) * Self::TILE_SIZE;
+                let center = Vec2::from(*first) + Vec2::new(size.x - Self::TILE_SIZE, 0.0) / 2.0;
+                gizmos.rect_2d(center, size, color);
+            }
+        }
+    }
+
+    // left click places the chosen enemy or picks the one on the cell for editing,
+    // right click removes the ones on the cell
+    fn place_enemy(
+        mut command: Commands,
+        window: Single<&Window>,
+        mut map_data: ResMut<MapData>,
+        mouse_buttons: Res<ButtonInput<MouseButton>>,
+        camera_transform: Single<&Transform, With<TilesMarker>>,
+        camera_projection: Single<&Projection, With<TilesMarker>>,
+    ) {
+        let Some(real_translation) =
+            Self::get_real_translation(window, camera_transform, camera_projection)
+        else {
+            return;
+        };
+        let position = (real_translation.x, real_translation.y);
+        if mouse_buttons.just_pressed(MouseButton::Left) {
+            let placed = map_data
+                .enemies
+                .iter()
+                .position(|enemy| enemy.position == position);
+            if let Some(spawn) = placed {
+                map_data.enemy = map_data.enemies[spawn].kind;
+                map_data.spawn = Some(spawn);
+            } else {
+                let kind = map_data.enemy;
+                map_data.enemies.push(EnemySpawn::new(position, kind));
+                map_data.spawn = Some(map_data.enemies.len() - 1);
+            }
+            command.trigger(UpdateEditLine);
+        } else if mouse_buttons.just_pressed(MouseButton::Right) {
+            map_data.enemies.retain(|enemy| enemy.position != position);
+            map_data.spawn = None;
+        }
+    }
+
+    // left click places a checkpoint, right click removes the one on the cell
+    fn place_checkpoint(
+        window: Single<&Window>,
+        mut map_data: ResMut<MapData>,
+        mouse_buttons: Res<ButtonInput<MouseButton>>,
+        camera_transform: Single<&Transform, With<TilesMarker>>,
+        camera_projection: Single<&Projection, With<TilesMarker>>,
+    ) {
+        let Some(real_translation) =
+            Self::get_real_translation(window, camera_transform, camera_projection)
+        else {
+            return;
+        };
+        let position = (real_translation.x, real_translation.y);
+        if mouse_buttons.just_pressed(MouseButton::Left)
+            && !map_data.checkpoints.contains(&position)
+        {
+            map_data.checkpoints.push(position);
+        } else if mouse_buttons.just_pressed(MouseButton::Right) {
+            map_data
+                .checkpoints
+                .retain(|checkpoint| *checkpoint != position);
+        }
+    }
+
+    // left click places a pickup of the chosen ability, right click removes the one on the cell
+    fn place_pickup(
+        window: Single<&Window>,
+        mut map_data: ResMut<MapData>,
+        mouse_buttons: Res<ButtonInput<MouseButton>>,
+        camera_transform: Single<&Transform, With<TilesMarker>>,
+        camera_projection: Single<&Projection, With<TilesMarker>>,
+    ) {
+        let Some(real_translation) =
+            Self::get_real_translation(window, camera_transform, camera_projection)
+        else {
+            return;
+        };
+        let position = (real_translation.x, real_translation.y);
+        if mouse_buttons.just_pressed(MouseButton::Left) {
+            let ability = map_data.pickup;
+            map_data
+                .pickups
+                .retain(|pickup| pickup.position != position);
+            map_data.pickups.push(AbilityPickup::new(position, ability));
+        } else if mouse_buttons.just_pressed(MouseButton::Right) {
+            map_data
+                .pickups
+                .retain(|pickup| pickup.position != position);
+        }
+    }
+
+    fn draw_pickups(map_data: Res<MapData>, mut gizmos: Gizmos) {
+        for pickup in &map_data.pickups {
+            let position = Vec2::from(pickup.position);
+            gizmos.rect_2d(
+                Isometry2d::new(position, Rot2::degrees(45.0)),
+                Vec2::splat(Self::TILE_SIZE / 2.0),
+                Self::PICKUP_COLOR,
+            );
+        }
+    }
+
+    fn draw_checkpoints(map_data: Res<MapData>, mut gizmos: Gizmos) {
+        for checkpoint in &map_data.checkpoints {
+            let position = Vec2::from(*checkpoint);
+            gizmos.rect_2d(
+                position,
+                Vec2::splat(Self::TILE_SIZE),
+                Self::CHECKPOINT_COLOR,
+            );
+            gizmos.line_2d(
+                position - Vec2::new(0.0, Self::TILE_SIZE / 2.0),
+                position + Vec2::new(0.0, Self::TILE_SIZE / 2.0),
+                Self::CHECKPOINT_COLOR,
+            );
+        }
+    }
+
+    // the kill plane under the grid, and the side walls next to it when they are on
+    fn draw_bounds(map_data: Res<MapData>, mut gizmos: Gizmos) {
+        let width = map_data.cols as f32 * Self::TILE_SIZE;
+        let height = map_data.rows as f32 * Self::TILE_SIZE;
+        let kill_plane = -map_data.kill_depth;
+        gizmos.line_2d(
+            Vec2::new(0.0, kill_plane),
+            Vec2::new(width, kill_plane),
+            Self::BOUNDS_COLOR,
+        );
+        if map_data.side_walls {
+            for x in [0.0, width] {
+                gizmos.line_2d(
+                    Vec2::new(x, kill_plane),
+                    Vec2::new(x, height),
+                    Self::BOUNDS_COLOR,
+                );
+            }
+        }
+    }
+
+    fn draw_enemies(map_data: Res<MapData>, mut gizmos: Gizmos) {
+        for (index, enemy) in map_data.enemies.iter().enumerate() {
+            let position = Vec2::from(enemy.position);
+            let edited = map_data.spawn == Some(index);
+            match enemy.kind {
+                EnemyKind::Wolf { patrol, .. } => {
+                    let color = if edited {
+                        Self::EDITED_ENEMY_COLOR
+                    } else {
+                        Self::WOLF_COLOR
+                    };
+                    gizmos.rect_2d(position, Vec2::splat(Self::TILE_SIZE), color);
+                    gizmos.line_2d(
+                        position - Vec2::new(patrol, 0.0),
+                        position + Vec2::new(patrol, 0.0),
+                        color,
+                    );
+                }
+                EnemyKind::Bat { range, .. } => {
+                    let color = if edited {
+                        Self::EDITED_ENEMY_COLOR
+                    } else {
+                        Self::BAT_COLOR
+                    };
+                    gizmos.circle_2d(position, Self::TILE_SIZE / 2.0, color);
+                    gizmos.circle_2d(position, range, color);
+                }
             }
         }
     }
@@ -360,7 +599,44 @@ impl Plugin for TilesPlugin {
             .add_systems(Update, Self::resize.run_if(in_state(AppState::Running)))
             .add_systems(
                 Update,
-                (Self::scale, Self::earse).run_if(in_state(UIState::Running)),
+                (
+                    Self::scale,
+                    Self::draw_paths,
+                    Self::draw_enemies,
+                    Self::draw_checkpoints,
+                    Self::draw_pickups,
+                    Self::draw_bounds,
+                )
+                    .run_if(in_state(UIState::Running)),
+            )
+            .add_systems(
+                Update,
+                Self::earse
+                    .run_if(in_state(EditorState::Tracking).or(in_state(EditorState::Selected))),
+            )
+            .add_systems(
+                Update,
+                Self::place_enemy.run_if(in_state(EditorState::Enemy)),
+            )
+            .add_systems(
+                Update,
+                Self::place_checkpoint.run_if(in_state(EditorState::Checkpoint)),
+            )
+            .add_systems(
+                Update,
+                Self::place_pickup.run_if(in_state(EditorState::Pickup)),
+            )
+            .add_systems(
+                Update,
+                Self::place_waypoint.run_if(in_state(EditorState::Path)),
+            )
+            .add_systems(
+                OnExit(EditorState::Path),
+                |mut map_data: ResMut<MapData>| map_data.finish_path(),
+            )
+            .add_systems(
+                OnExit(EditorState::Enemy),
+                |mut map_data: ResMut<MapData>| map_data.spawn = None,
             )
             .add_systems(
                 Update,
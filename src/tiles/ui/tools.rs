@@ -1,5 +1,6 @@
 use super::*;
 use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
     render::{camera::Viewport, view::RenderLayers},
     window::WindowResized,
 };
@@ -29,6 +30,135 @@ struct SaveButton;
 )]
 struct LoadButton;
 
+#[derive(Component)]
+#[require(
+    Node = ToolsPlugin::button_base_node(),
+    Button,
+    BorderRadius = BorderRadius::all(Val::Px(6.0)),
+    ToolsMarker
+)]
+struct FormatButton;
+
+#[derive(Component)]
+struct FormatButtonText;
+
+#[derive(Component)]
+#[require(
+    Node = ToolsPlugin::button_base_node(),
+    Button,
+    BorderRadius = BorderRadius::all(Val::Px(6.0)),
+    ToolsMarker
+)]
+struct LayerButton;
+
+#[derive(Component)]
+struct LayerButtonText;
+
+#[derive(Component)]
+#[require(
+    Node = ToolsPlugin::button_base_node(),
+    Button,
+    BorderRadius = BorderRadius::all(Val::Px(6.0)),
+    ToolsMarker
+)]
+struct LayerVisibleButton;
+
+#[derive(Component)]
+struct LayerVisibleButtonText;
+
+#[derive(Component)]
+#[require(
+    Node = ToolsPlugin::button_base_node(),
+    Button,
+    BorderRadius = BorderRadius::all(Val::Px(6.0)),
+    ToolsMarker
+)]
+struct PathButton;
+
+#[derive(Component)]
+#[require(
+    Node = ToolsPlugin::button_base_node(),
+    Button,
+    BorderRadius = BorderRadius::all(Val::Px(6.0)),
+    ToolsMarker
+)]
+struct PathModeButton;
+
+#[derive(Component)]
+struct PathModeButtonText;
+
+#[derive(Component)]
+#[require(
+    Node = ToolsPlugin::button_base_node(),
+    Button,
+    BorderRadius = BorderRadius::all(Val::Px(6.0)),
+    ToolsMarker
+)]
+struct EnemyButton;
+
+#[derive(Component)]
+#[require(
+    Node = ToolsPlugin::button_base_node(),
+    Button,
+    BorderRadius = BorderRadius::all(Val::Px(6.0)),
+    ToolsMarker
+)]
+struct EnemyKindButton;
+
+#[derive(Component)]
+struct EnemyKindButtonText;
+
+#[derive(Component)]
+#[require(
+    Node = ToolsPlugin::button_base_node(),
+    Button,
+    BorderRadius = BorderRadius::all(Val::Px(6.0)),
+    ToolsMarker
+)]
+struct CheckpointButton;
+
+#[derive(Component)]
+#[require(
+    Node = ToolsPlugin::button_base_node(),
+    Button,
+    BorderRadius = BorderRadius::all(Val::Px(6.0)),
+    ToolsMarker
+)]
+struct PickupButton;
+
+#[derive(Component)]
+#[require(
+    Node = ToolsPlugin::button_base_node(),
+    Button,
+    BorderRadius = BorderRadius::all(Val::Px(6.0)),
+    ToolsMarker
+)]
+struct PickupAbilityButton;
+
+#[derive(Component)]
+struct PickupAbilityButtonText;
+
+#[derive(Component)]
+#[require(
+    Node = ToolsPlugin::button_base_node(),
+    Button,
+    BorderRadius = BorderRadius::all(Val::Px(6.0)),
+    ToolsMarker
+)]
+struct SideWallsButton;
+
+#[derive(Component)]
+struct SideWallsButtonText;
+
+#[derive(Component)]
+#[require(
+    Node = ToolsPlugin::button_base_node(),
+    Button,
+    BorderRadius = BorderRadius::all(Val::Px(6.0)),
+    ToolsMarker
+)]
+struct AbilityButton(Ability);
+
 #[derive(Component)]
 #[require(
     Node = Node{
@@ -40,7 +170,7 @@ struct LoadButton;
     ImageNode,
     ToolsMarker,
 )]
-struct TileTypeButton;
+struct TileKindButton;
 
 #[derive(Component)]
 #[require(
@@ -55,6 +185,22 @@ struct TileTypeButton;
 )]
 struct TrackingButton;
 
+#[derive(Component)]
+#[require(
+    Node = Node{
+        width : Val::Px(32.0),
+        height : Val::Px(32.0),
+        ..Default::default()
+    },
+    Button,
+    ImageNode,
+    ToolsMarker,
+)]
+struct AtlasTileButton(usize);
+
+#[derive(Component)]
+struct AtlasPalette;
+
 #[derive(Component)]
 #[require(EditableText)]
 struct RotationEditLineText;
@@ -71,13 +217,83 @@ struct GridRowsEditLineText;
 #[require(EditableText)]
 struct NextLevelEditLineText;
 
+#[derive(Component)]
+#[require(EditableText)]
+struct PathWidthEditLineText;
+
+#[derive(Component)]
+#[require(EditableText)]
+struct PathSpeedEditLineText;
+
+#[derive(Component)]
+#[require(EditableText)]
+struct PathWaitEditLineText;
+
+#[derive(Component)]
+#[require(EditableText)]
+struct KillDepthEditLineText;
+
+#[derive(Component)]
+#[require(EditableText)]
+struct GravityEditLineText;
+
+#[derive(Component)]
+#[require(EditableText)]
+struct EnemyRangeEditLineText;
+
+#[derive(Component)]
+#[require(EditableText)]
+struct EnemySpeedEditLineText;
+
+#[derive(Component)]
+#[require(EditableText)]
+struct EnemySightEditLineText;
+
 #[derive(Event)]
 pub struct UpdateEditLine;
 
+type MapEditLineTextParamSet<'w, 's> = ParamSet<
+    'w,
+    's,
+    (
+        Single<'static, &'static mut Text, With<GridColsEditLineText>>,
+        Single<'static, &'static mut Text, With<GridRowsEditLineText>>,
+        Single<'static, &'static mut Text, With<NextLevelEditLineText>>,
+        Single<'static, &'static mut Text, With<FormatButtonText>>,
+        Single<'static, &'static mut Text, With<LayerButtonText>>,
+        Single<'static, &'static mut Text, With<LayerVisibleButtonText>>,
+        Single<'static, &'static mut Text, With<KillDepthEditLineText>>,
+        Single<'static, &'static mut Text, With<SideWallsButtonText>>,
+    ),
+>;
+
+type EnemyEditLineTextParamSet<'w, 's> = ParamSet<
+    'w,
+    's,
+    (
+        Single<'static, &'static mut Text, With<EnemyKindButtonText>>,
+        Single<'static, &'static mut Text, With<EnemyRangeEditLineText>>,
+        Single<'static, &'static mut Text, With<EnemySpeedEditLineText>>,
+        Single<'static, &'static mut Text, With<EnemySightEditLineText>>,
+    ),
+>;
+
+type ToolsInteractionQuery<'a, 'b, 'c> =
+    Query<'a, 'b, (&'c Interaction, Entity), (Changed<Interaction>, With<ToolsMarker>)>;
+
 pub(super) struct ToolsPlugin;
 impl ToolsPlugin {
     const SVAE_BUTTON_LAB: &'static str = "save";
     const LOAD_BUTTON_LAB: &'static str = "load";
+    const LAYER_SHOWN_LAB: &'static str = "shown";
+    const LAYER_HIDDEN_LAB: &'static str = "hidden";
+    const PATH_BUTTON_LAB: &'static str = "path";
+    const ENEMY_BUTTON_LAB: &'static str = "enemy";
+    const CHECKPOINT_BUTTON_LAB: &'static str = "checkpoint";
+    const PICKUP_BUTTON_LAB: &'static str = "pickup";
+    const SIDE_WALLS_ON_LAB: &'static str = "walls";
+    const SIDE_WALLS_OFF_LAB: &'static str = "no walls";
+    const PALETTE_LINE_HEIGHT: f32 = 34.0;
 
     const DEFAULT_OUTLINE: Outline = Outline::new(Val::Px(1.0), Val::ZERO, Color::WHITE);
     const SELECTED_OUTLINE: Outline =
@@ -106,7 +322,12 @@ impl ToolsPlugin {
         }
     }
 
-    fn init(mut command: Commands, level_resource: Res<LevelStaticResource>) {
+    fn init(
+        mut command: Commands,
+        level_resource: Res<LevelStaticResource>,
+        tile_registry: Res<Assets<TileRegistry>>,
+    ) {
+        let registry = tile_registry.get(&level_resource.tiles_handle).unwrap();
         let tool_camera_id = Self::create_camera(&mut command);
         let mut id = Entity::PLACEHOLDER;
         command
@@ -127,12 +348,19 @@ impl ToolsPlugin {
             .with_children(|parent| {
                 Self::create_buttons(parent);
                 Self::craete_editlines(parent);
-                id = Self::create_elements(parent, &level_resource);
+                id = Self::create_elements(parent, registry, &level_resource);
+                Self::create_palette(parent, &level_resource);
             });
+        let first = registry.tiles.first();
         command.insert_resource(Selected {
             id,
-            typ: TileType::Wall,
+            kind: first
+                .map(|definition| definition.name.clone())
+                .unwrap_or_default(),
             rotation: 0.0,
+            atlas_index: first
+                .map(|definition| definition.frames.0)
+                .unwrap_or_default(),
         });
         command.trigger_targets(UIButtonDown, id);
     }
@@ -179,9 +407,240 @@ impl ToolsPlugin {
                         },
                     );
             });
+        command
+            .spawn(Self::line_base_node())
+            .with_children(|command| {
+                command
+                    .spawn((
+                        FormatButton,
+                        BorderColor(Color::BLACK),
+                        children![(
+                            Text::new(LevelFormat::default().name()),
+                            FormatButtonText
+                        )],
+                    ))
+                    .observe(
+                        |_: Trigger<UIButtonDown>,
+                         mut map_data: ResMut<MapData>,
+                         mut text: Single<&mut Text, With<FormatButtonText>>| {
+                            map_data.format = map_data.format.next();
+                            text.0 = map_data.format.name().to_string();
+                        },
+                    );
+            });
+        command
+            .spawn(Self::line_base_node())
+            .with_children(|command| {
+                command
+                    .spawn((
+                        LayerButton,
+                        BorderColor(Color::BLACK),
+                        children![(Text::new(TileLayer::COLLISION), LayerButtonText)],
+                    ))
+                    .observe(
+                        |_: Trigger<UIButtonDown>,
+                         mut command: Commands,
+                         mut map_data: ResMut<MapData>| {
+                            map_data.layer = (map_data.layer + 1) % map_data.layers.len().max(1);
+                            command.trigger(UpdateEditLine);
+                        },
+                    );
+                command
+                    .spawn((
+                        LayerVisibleButton,
+                        BorderColor(Color::BLACK),
+                        children![(Text::new(Self::LAYER_SHOWN_LAB), LayerVisibleButtonText)],
+                    ))
+                    .observe(
+                        |_: Trigger<UIButtonDown>,
+                         mut command: Commands,
+                         mut map_data: ResMut<MapData>| {
+                            let layer = map_data.current_mut();
+                            layer.visible = !layer.visible;
+                            let visibility = layer.visibility();
+                            for tile_data in layer.data.values() {
+                                command.entity(tile_data.id).insert(visibility);
+                            }
+                            command.trigger(UpdateEditLine);
+                        },
+                    );
+            });
+        command
+            .spawn(Self::line_base_node())
+            .with_children(|command| {
+                // a new path uses the selected tile, waypoints are placed on the tile map
+                command
+                    .spawn((
+                        PathButton,
+                        BorderColor(Color::BLACK),
+                        children![Text::new(Self::PATH_BUTTON_LAB)],
+                    ))
+                    .observe(
+                        |_: Trigger<UIButtonDown>,
+                         selected: Res<Selected>,
+                         mut map_data: ResMut<MapData>,
+                         mut editor_next_state: ResMut<NextState<EditorState>>| {
+                            map_data.finish_path();
+                            map_data.start_path(&selected);
+                            editor_next_state.set(EditorState::Path);
+                        },
+                    );
+                command
+                    .spawn((
+                        PathModeButton,
+                        BorderColor(Color::BLACK),
+                        children![(Text::new(PathMode::default().name()), PathModeButtonText)],
+                    ))
+                    .observe(
+                        |_: Trigger<UIButtonDown>,
+                         mut map_data: ResMut<MapData>,
+                         mut text: Single<&mut Text, With<PathModeButtonText>>| {
+                            map_data.path_settings.mode = map_data.path_settings.mode.next();
+                            map_data.apply_path_settings();
+                            text.0 = map_data.path_settings.mode.name().to_string();
+                        },
+                    );
+            });
+        command
+            .spawn(Self::line_base_node())
+            .with_children(|command| {
+                command
+                    .spawn((
+                        EnemyButton,
+                        BorderColor(Color::BLACK),
+                        children![Text::new(Self::ENEMY_BUTTON_LAB)],
+                    ))
+                    .observe(
+                        |_: Trigger<UIButtonDown>,
+                         mut editor_next_state: ResMut<NextState<EditorState>>| {
+                            editor_next_state.set(EditorState::Enemy);
+                        },
+                    );
+                command
+                    .spawn((
+                        EnemyKindButton,
+                        BorderColor(Color::BLACK),
+                        children![(
+                            Text::new(Self::enemy_label(&EnemyKind::WOLF)),
+                            EnemyKindButtonText
+                        )],
+                    ))
+                    .observe(
+                        |_: Trigger<UIButtonDown>,
+                         mut command: Commands,
+                         mut map_data: ResMut<MapData>| {
+                            // another kind starts a new enemy, the edited one keeps its own
+                            map_data.enemy = map_data.enemy.next();
+                            map_data.spawn = None;
+                            command.trigger(UpdateEditLine);
+                        },
+                    );
+            });
+        command
+            .spawn(Self::line_base_node())
+            .with_children(|command| {
+                command
+                    .spawn((
+                        CheckpointButton,
+                        BorderColor(Color::BLACK),
+                        children![Text::new(Self::CHECKPOINT_BUTTON_LAB)],
+                    ))
+                    .observe(
+                        |_: Trigger<UIButtonDown>,
+                         mut editor_next_state: ResMut<NextState<EditorState>>| {
+                            editor_next_state.set(EditorState::Checkpoint);
+                        },
+                    );
+                command
+                    .spawn((
+                        SideWallsButton,
+                        BorderColor(Color::BLACK),
+                        children![(Text::new(Self::SIDE_WALLS_ON_LAB), SideWallsButtonText)],
+                    ))
+                    .observe(
+                        |_: Trigger<UIButtonDown>,
+                         mut command: Commands,
+                         mut map_data: ResMut<MapData>| {
+                            map_data.side_walls = !map_data.side_walls;
+                            command.trigger(UpdateEditLine);
+                        },
+                    );
+            });
+        // abilities the level unlocks, outlined in white while on
+        command
+            .spawn(Self::line_base_node())
+            .with_children(|command| {
+                for ability in Ability::iter() {
+                    command
+                        .spawn((
+                            AbilityButton(ability),
+                            BorderColor(Color::BLACK),
+                            children![Text::new(ability.name())],
+                        ))
+                        .observe(
+                            move |_: Trigger<UIButtonDown>,
+                                  mut command: Commands,
+                                  mut map_data: ResMut<MapData>| {
+                                if !map_data.abilities.unlock(ability) {
+                                    map_data.abilities.lock(ability);
+                                }
+                                command.trigger(UpdateEditLine);
+                            },
+                        );
+                }
+            });
+        command
+            .spawn(Self::line_base_node())
+            .with_children(|command| {
+                command
+                    .spawn((
+                        PickupButton,
+                        BorderColor(Color::BLACK),
+                        children![Text::new(Self::PICKUP_BUTTON_LAB)],
+                    ))
+                    .observe(
+                        |_: Trigger<UIButtonDown>,
+                         mut editor_next_state: ResMut<NextState<EditorState>>| {
+                            editor_next_state.set(EditorState::Pickup);
+                        },
+                    );
+                command
+                    .spawn((
+                        PickupAbilityButton,
+                        BorderColor(Color::BLACK),
+                        children![(
+                            Text::new(Ability::default().name()),
+                            PickupAbilityButtonText
+                        )],
+                    ))
+                    .observe(
+                        |_: Trigger<UIButtonDown>,
+                         mut map_data: ResMut<MapData>,
+                         mut text: Single<&mut Text, With<PickupAbilityButtonText>>| {
+                            map_data.pickup = map_data.pickup.next();
+                            text.0 = map_data.pickup.name().to_string();
+                        },
+                    );
+            });
+    }
+
+    fn enemy_label(kind: &EnemyKind) -> String {
+        match kind {
+            EnemyKind::Wolf { .. } => kind.name().to_string(),
+            EnemyKind::Bat { pattern, .. } => format!("{} {}", kind.name(), pattern.name()),
+        }
     }
 
     fn craete_editlines(command: &mut ChildSpawnerCommands) {
+        // the enemy placed by the first click
+        let EnemyKind::Wolf {
+            patrol,
+            speed,
+            sight,
+        } = EnemyKind::WOLF
+        else {
+            unreachable!();
+        };
         command
             .spawn(Self::line_base_node())
             .with_children(|parent| {
@@ -246,9 +705,8 @@ impl ToolsPlugin {
                     "none",
                     |_: Trigger<EditFinished>,
                      text: Single<&Text, With<NextLevelEditLineText>>,
-                     mut map_data: ResMut<MapData>|
-                     -> Result {
-                        Ok(map_data.as_mut().next = text.parse::<usize>().ok())
+                     mut map_data: ResMut<MapData>| {
+                        map_data.as_mut().next = text.parse::<usize>().ok();
                     },
                 );
 
@@ -261,7 +719,152 @@ impl ToolsPlugin {
                      text: Single<&Text, With<RotationEditLineText>>,
                      mut selected: ResMut<Selected>|
                      -> Result {
-                        Ok(selected.as_mut().rotation = text.parse::<f32>()?.to_radians())
+                        selected.as_mut().rotation = text.parse::<f32>()?.to_radians();
+                        Ok(())
+                    },
+                );
+            });
+        command
+            .spawn(Self::line_base_node())
+            .with_children(|parent| {
+                Self::create_editline_node(
+                    parent,
+                    PathWidthEditLineText,
+                    "width",
+                    &MovingPlatform::DEFAULT_WIDTH.to_string(),
+                    |_: Trigger<EditFinished>,
+                     text: Single<&Text, With<PathWidthEditLineText>>,
+                     mut map_data: ResMut<MapData>|
+                     -> Result {
+                        map_data.path_settings.width = text.parse::<usize>()?.max(1);
+                        map_data.apply_path_settings();
+                        Ok(())
+                    },
+                );
+                Self::create_editline_node(
+                    parent,
+                    PathSpeedEditLineText,
+                    "speed",
+                    &MovingPlatform::DEFAULT_SPEED.to_string(),
+                    |_: Trigger<EditFinished>,
+                     text: Single<&Text, With<PathSpeedEditLineText>>,
+                     mut map_data: ResMut<MapData>|
+                     -> Result {
+                        map_data.path_settings.speed = text.parse::<f32>()?;
+                        map_data.apply_path_settings();
+                        Ok(())
+                    },
+                );
+            });
+        command
+            .spawn(Self::line_base_node())
+            .with_children(|parent| {
+                Self::create_editline_node(
+                    parent,
+                    PathWaitEditLineText,
+                    "wait",
+                    &MovingPlatform::DEFAULT_WAIT.to_string(),
+                    |_: Trigger<EditFinished>,
+                     text: Single<&Text, With<PathWaitEditLineText>>,
+                     mut map_data: ResMut<MapData>|
+                     -> Result {
+                        map_data.path_settings.wait = text.parse::<f32>()?;
+                        map_data.apply_path_settings();
+                        Ok(())
+                    },
+                );
+                Self::create_editline_node(
+                    parent,
+                    KillDepthEditLineText,
+                    "kill depth",
+                    &LevelAsset::DEFAULT_KILL_DEPTH.to_string(),
+                    |_: Trigger<EditFinished>,
+                     text: Single<&Text, With<KillDepthEditLineText>>,
+                     mut map_data: ResMut<MapData>|
+                     -> Result {
+                        map_data.kill_depth = text.parse::<f32>()?.max(0.0);
+                        Ok(())
+                    },
+                );
+            });
+        command
+            .spawn(Self::line_base_node())
+            .with_children(|parent| {
+                // none keeps the gravity of the movement profile
+                Self::create_editline_node(
+                    parent,
+                    GravityEditLineText,
+                    "gravity",
+                    "none",
+                    |_: Trigger<EditFinished>,
+                     text: Single<&Text, With<GravityEditLineText>>,
+                     mut map_data: ResMut<MapData>| {
+                        map_data.gravity = text
+                            .parse::<f32>()
+                            .ok()
+                            .filter(|gravity| gravity.is_finite() && *gravity > 0.0);
+                    },
+                );
+                // how far a wolf patrols or a bat flies and dives
+                Self::create_editline_node(
+                    parent,
+                    EnemyRangeEditLineText,
+                    "enemy range",
+                    &patrol.to_string(),
+                    |_: Trigger<EditFinished>,
+                     text: Single<&Text, With<EnemyRangeEditLineText>>,
+                     mut map_data: ResMut<MapData>|
+                     -> Result {
+                        let value = text.parse::<f32>()?.max(0.0);
+                        match &mut map_data.enemy {
+                            EnemyKind::Wolf { patrol, .. } => *patrol = value,
+                            EnemyKind::Bat { range, .. } => *range = value,
+                        }
+                        map_data.apply_enemy();
+                        Ok(())
+                    },
+                );
+            });
+        command
+            .spawn(Self::line_base_node())
+            .with_children(|parent| {
+                Self::create_editline_node(
+                    parent,
+                    EnemySpeedEditLineText,
+                    "enemy speed",
+                    &speed.to_string(),
+                    |_: Trigger<EditFinished>,
+                     text: Single<&Text, With<EnemySpeedEditLineText>>,
+                     mut map_data: ResMut<MapData>|
+                     -> Result {
+                        let value = text.parse::<f32>()?;
+                        match &mut map_data.enemy {
+                            EnemyKind::Wolf { speed, .. } | EnemyKind::Bat { speed, .. } => {
+                                *speed = value
+                            }
+                        }
+                        map_data.apply_enemy();
+                        Ok(())
+                    },
+                );
+                // only wolves look for the player, a diving bat swoops within twice its range
+                Self::create_editline_node(
+                    parent,
+                    EnemySightEditLineText,
+                    "sight",
+                    &sight.to_string(),
+                    |_: Trigger<EditFinished>,
+                     mut command: Commands,
+                     text: Single<&Text, With<EnemySightEditLineText>>,
+                     mut map_data: ResMut<MapData>|
+                     -> Result {
+                        let value = text.parse::<f32>()?.max(0.0);
+                        if let EnemyKind::Wolf { sight, .. } = &mut map_data.enemy {
+                            *sight = value;
+                        }
+                        map_data.apply_enemy();
+                        command.trigger(UpdateEditLine);
+                        Ok(())
                     },
                 );
             });
@@ -293,14 +896,55 @@ impl ToolsPlugin {
             });
     }
 
+    fn update_ability_buttons(
+        _: Trigger<UpdateEditLine>,
+        map_data: Res<MapData>,
+        buttons: Query<(&AbilityButton, &mut BorderColor)>,
+    ) {
+        for (button, mut border) in buttons {
+            border.0 = if map_data.abilities.has(button.0) {
+                Color::WHITE
+            } else {
+                Color::BLACK
+            };
+        }
+    }
+
+    fn update_gravity_editline(
+        _: Trigger<UpdateEditLine>,
+        map_data: Res<MapData>,
+        mut text: Single<&mut Text, With<GravityEditLineText>>,
+    ) {
+        text.0 = if let Some(gravity) = map_data.gravity {
+            gravity.to_string()
+        } else {
+            String::from("none")
+        };
+    }
+
+    fn update_enemy_editlines(
+        _: Trigger<UpdateEditLine>,
+        map_data: Res<MapData>,
+        mut paramset: EnemyEditLineTextParamSet,
+    ) {
+        let (range, speed, sight) = match map_data.enemy {
+            EnemyKind::Wolf {
+                patrol,
+                speed,
+                sight,
+            } => (patrol, speed, sight.to_string()),
+            EnemyKind::Bat { range, speed, .. } => (range, speed, String::from("none")),
+        };
+        paramset.p0().0 = Self::enemy_label(&map_data.enemy);
+        paramset.p1().0 = range.to_string();
+        paramset.p2().0 = speed.to_string();
+        paramset.p3().0 = sight;
+    }
+
     fn update_editlines(
         _: Trigger<UpdateEditLine>,
         map_data: Res<MapData>,
-        mut paramset: ParamSet<(
-            Single<&mut Text, With<GridColsEditLineText>>,
-            Single<&mut Text, With<GridRowsEditLineText>>,
-            Single<&mut Text, With<NextLevelEditLineText>>,
-        )>,
+        mut paramset: MapEditLineTextParamSet,
     ) {
         paramset.p0().0 = map_data.cols.to_string();
         paramset.p1().0 = map_data.rows.to_string();
@@ -309,10 +953,28 @@ impl ToolsPlugin {
         } else {
             String::from("none")
         };
+        paramset.p3().0 = map_data.format.name().to_string();
+        if let Some(layer) = map_data.layers.get(map_data.layer) {
+            paramset.p4().0 = layer.name.clone();
+            paramset.p5().0 = if layer.visible {
+                Self::LAYER_SHOWN_LAB
+            } else {
+                Self::LAYER_HIDDEN_LAB
+            }
+            .to_string();
+        }
+        paramset.p6().0 = map_data.kill_depth.to_string();
+        paramset.p7().0 = if map_data.side_walls {
+            Self::SIDE_WALLS_ON_LAB
+        } else {
+            Self::SIDE_WALLS_OFF_LAB
+        }
+        .to_string();
     }
 
     fn create_elements(
         command: &mut ChildSpawnerCommands,
+        registry: &TileRegistry,
         tiles_resource: &Res<LevelStaticResource>,
     ) -> Entity {
         let mut id = Entity::PLACEHOLDER;
@@ -324,8 +986,8 @@ impl ToolsPlugin {
             })
             .with_children(|parent| {
                 id = Self::create_tracking(parent);
-                for tile_type in TileType::iter() {
-                    Self::create_choice(parent, tile_type, &tiles_resource);
+                for definition in &registry.tiles {
+                    Self::create_choice(parent, definition, tiles_resource);
                 }
             });
         id
@@ -359,17 +1021,19 @@ impl ToolsPlugin {
 
     fn create_choice(
         command: &mut ChildSpawnerCommands,
-        tile_type: TileType,
+        definition: &TileDefinition,
         tiles_resource: &Res<LevelStaticResource>,
     ) {
+        let kind = definition.name.clone();
+        let atlas_index = definition.frames.0;
         command
             .spawn((
-                TileTypeButton,
+                TileKindButton,
                 ImageNode {
                     image: tiles_resource.texture_handle.clone(),
                     texture_atlas: Some(TextureAtlas {
                         layout: tiles_resource.layout_handle.clone(),
-                        index: tile_type.texture_atlas_index(),
+                        index: atlas_index,
                     }),
                     ..Default::default()
                 },
@@ -385,12 +1049,90 @@ impl ToolsPlugin {
                         .entity(trigger.target())
                         .insert(Self::SELECTED_OUTLINE);
                     selected.as_mut().id = trigger.target();
-                    selected.as_mut().typ = tile_type;
+                    selected.as_mut().kind = kind.clone();
+                    selected.as_mut().atlas_index = atlas_index;
                     editor_next_state.set(EditorState::Selected);
                 },
             );
     }
 
+    // every atlas tile, picking one changes how the selected tile kind is drawn
+    fn create_palette(
+        command: &mut ChildSpawnerCommands,
+        tiles_resource: &Res<LevelStaticResource>,
+    ) {
+        command
+            .spawn((
+                Node {
+                    width: Val::Percent(100.0),
+                    min_height: Val::Px(0.0),
+                    flex_grow: 1.0,
+                    flex_wrap: FlexWrap::Wrap,
+                    align_content: AlignContent::FlexStart,
+                    column_gap: Val::Px(2.0),
+                    row_gap: Val::Px(2.0),
+                    overflow: Overflow::scroll_y(),
+                    ..Default::default()
+                },
+                AtlasPalette,
+            ))
+            .with_children(|parent| {
+                for index in 0..TileAtlas::LEN {
+                    parent
+                        .spawn((
+                            AtlasTileButton(index),
+                            ImageNode {
+                                image: tiles_resource.texture_handle.clone(),
+                                texture_atlas: Some(TextureAtlas {
+                                    layout: tiles_resource.layout_handle.clone(),
+                                    index,
+                                }),
+                                ..Default::default()
+                            },
+                            Self::DEFAULT_OUTLINE,
+                        ))
+                        .observe(
+                            move |_: Trigger<UIButtonDown>, mut selected: ResMut<Selected>| {
+                                selected.as_mut().atlas_index = index;
+                            },
+                        );
+                }
+            });
+    }
+
+    fn highlight_palette(
+        selected: Res<Selected>,
+        buttons: Query<(&AtlasTileButton, &mut Outline)>,
+    ) {
+        for (button, mut outline) in buttons {
+            *outline = if button.0 == selected.atlas_index {
+                Self::SELECTED_OUTLINE
+            } else {
+                Self::DEFAULT_OUTLINE
+            };
+        }
+    }
+
+    fn scroll_palette(
+        window: Single<&Window>,
+        mut mouse_wheel: EventReader<MouseWheel>,
+        mut palette: Single<&mut ScrollPosition, With<AtlasPalette>>,
+    ) {
+        let delta = mouse_wheel
+            .read()
+            .map(|wheel| match wheel.unit {
+                MouseScrollUnit::Line => wheel.y * Self::PALETTE_LINE_HEIGHT,
+                MouseScrollUnit::Pixel => wheel.y,
+            })
+            .sum::<f32>();
+        if window
+            .cursor_position()
+            .is_some_and(|cursor| cursor.x > window.width() * UIPlugin::TILE_VIEWPORT_VAL.x)
+        {
+            palette.offset_y = (palette.offset_y - delta).max(0.0);
+        }
+    }
+
     fn resize(
         window: Single<&Window>,
         mut window_event: EventReader<WindowResized>,
@@ -409,10 +1151,7 @@ impl ToolsPlugin {
         }
     }
 
-    fn handle_clicked(
-        mut command: Commands,
-        interactions: Query<(&Interaction, Entity), (Changed<Interaction>, With<ToolsMarker>)>,
-    ) {
+    fn handle_clicked(mut command: Commands, interactions: ToolsInteractionQuery) {
         for interaction in interactions {
             if let Interaction::Pressed = interaction.0 {
                 command.trigger_targets(UIButtonDown, interaction.1);
@@ -425,11 +1164,22 @@ impl Plugin for ToolsPlugin {
         app.add_event::<UIButtonDown>()
             .add_event::<UpdateEditLine>()
             .add_observer(Self::update_editlines)
+            .add_observer(Self::update_ability_buttons)
+            .add_observer(Self::update_gravity_editline)
+            .add_observer(Self::update_enemy_editlines)
             .add_systems(OnEnter(AppState::Running), Self::init)
             .add_systems(Update, Self::resize.run_if(in_state(AppState::Running)))
             .add_systems(
                 Update,
                 Self::handle_clicked.run_if(in_state(UIState::Running)),
+            )
+            .add_systems(
+                Update,
+                (
+                    Self::scroll_palette,
+                    Self::highlight_palette.run_if(resource_changed::<Selected>),
+                )
+                    .run_if(in_state(UIState::Running)),
             );
     }
 }
@@ -30,6 +30,13 @@ impl EditLine {
 #[component(storage = "SparseSet")]
 struct CurrentEditable;
 
+type EditLineInteractionQuery<'a, 'b, 'c> = Query<
+    'a,
+    'b,
+    (Entity, &'c Interaction, &'c EditableTextEntity),
+    (With<EditLine>, Changed<Interaction>),
+>;
+
 pub struct EditLinePlugin;
 impl EditLinePlugin {
     pub fn spawn_edit<E, B, M, K>(
@@ -144,10 +151,7 @@ impl EditLinePlugin {
         mut command: Commands,
         text_query: Query<&Text, With<EditableText>>,
         current_entry: Option<Single<Entity, With<CurrentEditable>>>,
-        next_entrys: Query<
-            (Entity, &Interaction, &EditableTextEntity),
-            (With<EditLine>, Changed<Interaction>),
-        >,
+        next_entrys: EditLineInteractionQuery,
         mut cursor_position_query: Query<&mut CursorPosition, With<EditableText>>,
     ) {
         for (entity, interaction, editable_text_entity) in next_entrys {
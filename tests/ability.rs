@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+use sara_level::prelude::*;
+
+mod common;
+
+fn level(abilities: AbilitySet) -> LevelAsset {
+    LevelAsset {
+        entry: (16.0, 16.0),
+        abilities,
+        ..LevelAsset::new(4, 4)
+    }
+}
+
+#[test]
+fn unlocking_twice_changes_nothing() {
+    let mut abilities = AbilitySet::default();
+    assert!(abilities.unlock(Ability::WallSlide));
+    assert!(!abilities.unlock(Ability::WallSlide));
+    assert!(abilities.has(Ability::WallSlide));
+    assert!(!abilities.has(Ability::WallJump));
+    assert!(abilities.lock(Ability::WallSlide));
+    assert!(abilities.is_empty());
+}
+
+#[test]
+fn level_abilities_add_to_the_run() {
+    let mut run = AbilitySet::from_iter([Ability::WallSlide]);
+    run.extend(&AbilitySet::from_iter([Ability::WallJump]));
+    assert_eq!(
+        run.iter().collect::<Vec<_>>(),
+        [Ability::WallSlide, Ability::WallJump]
+    );
+}
+
+#[test]
+fn abilities_are_named_in_text_levels() {
+    let level = level(AbilitySet::from_iter([Ability::WallSlide]));
+    let text = String::from_utf8(level.encode(LevelFormat::Text).unwrap()).unwrap();
+    assert!(text.contains("\"wall_slide\""), "{text}");
+}
+
+#[test]
+fn older_levels_unlock_nothing() {
+    let level = LevelAsset::decode(
+        &std::fs::read(LevelAsset::file_path(0, LevelFormat::Binary)).unwrap(),
+        LevelFormat::Binary,
+    )
+    .unwrap();
+    assert!(level.abilities.is_empty());
+}
+
+#[test]
+fn cooldowns_run_out() {
+    let mut cooldowns = AbilityCooldowns::default();
+    cooldowns.start(Ability::AirDash);
+    cooldowns.start(Ability::WallJump);
+    assert!(!cooldowns.is_ready(Ability::AirDash));
+    // abilities without a cooldown are always ready
+    assert!(cooldowns.is_ready(Ability::WallJump));
+    cooldowns.tick(Ability::AirDash.cooldown() / 2.0);
+    assert!(!cooldowns.is_ready(Ability::AirDash));
+    cooldowns.tick(Ability::AirDash.cooldown());
+    assert!(cooldowns.is_ready(Ability::AirDash));
+}
+
+#[test]
+fn broken_pickups_are_reported() {
+    let registry = common::registry();
+    let wall = registry.get("wall").unwrap();
+    let mut level = level(AbilitySet::default());
+    level.layers =
+        vec![TileLayer::collision().with_data(vec![TileDescriptor::new((80.0, 16.0), wall, 0.0)])];
+    level.pickups = vec![
+        AbilityPickup::new((80.0, 16.0), Ability::AirDash),
+        AbilityPickup::new((16.0, 400.0), Ability::DoubleJump),
+    ];
+    let issues = level.validate(&LevelAsset::data_dir(), &registry);
+    assert!(issues.contains(&LevelIssue::PickupInWall(0)));
+    assert!(issues.contains(&LevelIssue::PickupOutOfBounds {
+        index: 1,
+        ability: Ability::DoubleJump,
+        cell: IVec2::new(0, 12),
+    }));
+}
+
+#[test]
+fn saves_keep_unlocked_abilities() {
+    let path = std::env::temp_dir().join(format!("sara-save-{}.json", std::process::id()));
+    assert_eq!(SaveData::read(&path).unwrap(), SaveData::default());
+    let save = SaveData {
+        abilities: AbilitySet::from_iter([Ability::DoubleJump, Ability::AirDash]),
+    };
+    save.write(&path).unwrap();
+    let read = SaveData::read(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(read.unwrap(), save);
+}
@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+use sara_level::prelude::*;
+
+mod common;
+
+fn level() -> LevelAsset {
+    LevelAsset {
+        entry: (16.0, 16.0),
+        ..LevelAsset::new(4, 6)
+    }
+}
+
+#[test]
+fn bounds_cover_the_grid() {
+    let bounds = level().bounds();
+    assert_eq!(bounds.min, Vec2::ZERO);
+    assert_eq!(
+        bounds.max,
+        Vec2::new(6.0, 4.0) * TileAtlas::TILE_SIZE.as_vec2()
+    );
+}
+
+#[test]
+fn kill_plane_sits_below_the_grid() {
+    let mut level = level();
+    assert_eq!(level.kill_plane(), -LevelAsset::DEFAULT_KILL_DEPTH);
+    level.kill_depth = 0.0;
+    assert_eq!(level.kill_plane(), level.bounds().min.y);
+}
+
+#[test]
+fn older_levels_get_default_bounds() {
+    let level = LevelAsset::decode(
+        &std::fs::read(LevelAsset::file_path(0, LevelFormat::Binary)).unwrap(),
+        LevelFormat::Binary,
+    )
+    .unwrap();
+    assert_eq!(level.kill_depth, LevelAsset::DEFAULT_KILL_DEPTH);
+    assert!(level.side_walls);
+}
+
+#[test]
+fn negative_kill_depth_is_reported() {
+    let mut level = level();
+    level.kill_depth = -32.0;
+    let issues = level.validate(&LevelAsset::data_dir(), &common::registry());
+    assert!(issues.contains(&LevelIssue::InvalidKillDepth(-32.0)));
+}
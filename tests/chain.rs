@@ -0,0 +1,58 @@
+use sara_level::prelude::*;
+
+// levels by id, each pointing at its next one
+fn pack(links: &[(usize, Option<usize>)]) -> LevelPack {
+    LevelPack {
+        levels: links
+            .iter()
+            .map(|&(id, next)| {
+                (
+                    id,
+                    LevelAsset {
+                        next,
+                        ..LevelAsset::new(1, 1)
+                    },
+                )
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn a_straight_chain_is_clean() {
+    let pack = pack(&[(0, Some(1)), (1, Some(2)), (2, None)]);
+    assert_eq!(pack.validate_chain(), []);
+}
+
+#[test]
+fn cycles_are_reported_once_from_their_lowest_id() {
+    let pack = pack(&[(0, Some(1)), (1, Some(2)), (2, Some(1))]);
+    assert_eq!(pack.validate_chain(), [ChainIssue::Cycle(vec![1, 2])]);
+}
+
+#[test]
+fn levels_off_the_chain_are_unreachable() {
+    let pack = pack(&[(0, Some(1)), (1, None), (2, None)]);
+    assert_eq!(pack.validate_chain(), [ChainIssue::Unreachable(2)]);
+}
+
+#[test]
+fn a_next_without_a_level_dangles() {
+    let pack = pack(&[(0, Some(1)), (1, Some(5))]);
+    assert_eq!(
+        pack.validate_chain(),
+        [ChainIssue::DanglingNext { from: 1, to: 5 }]
+    );
+}
+
+#[test]
+fn a_pack_without_the_first_level_reaches_nothing() {
+    let pack = pack(&[(1, None)]);
+    assert_eq!(
+        pack.validate_chain(),
+        [
+            ChainIssue::MissingFirst(LevelAsset::FIRST),
+            ChainIssue::Unreachable(1)
+        ]
+    );
+}
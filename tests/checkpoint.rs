@@ -0,0 +1,29 @@
+use bevy::prelude::*;
+use sara_level::prelude::*;
+
+mod common;
+
+fn level(checkpoints: Vec<(f32, f32)>, walls: Vec<TileDescriptor>) -> LevelAsset {
+    LevelAsset {
+        layers: vec![TileLayer::collision().with_data(walls)],
+        entry: (16.0, 16.0),
+        checkpoints,
+        ..LevelAsset::new(4, 6)
+    }
+}
+
+#[test]
+fn broken_checkpoints_are_reported() {
+    let registry = common::registry();
+    let wall = registry.get("wall").unwrap();
+    let level = level(
+        vec![(80.0, 16.0), (16.0, 400.0)],
+        vec![TileDescriptor::new((80.0, 16.0), wall, 0.0)],
+    );
+    let issues = level.validate(&LevelAsset::data_dir(), &registry);
+    assert!(issues.contains(&LevelIssue::CheckpointInWall(0)));
+    assert!(issues.contains(&LevelIssue::CheckpointOutOfBounds {
+        index: 1,
+        cell: IVec2::new(0, 12),
+    }));
+}
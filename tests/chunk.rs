@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use sara_level::prelude::*;
+
+mod common;
+
+fn descriptor(cell: IVec2, kind: &str) -> TileDescriptor {
+    let registry = common::registry();
+    let size = TileAtlas::TILE_SIZE.as_vec2();
+    let pos = cell.as_vec2() * size + size / 2.0;
+    TileDescriptor::new((pos.x, pos.y), registry.get(kind).unwrap(), 0.0)
+}
+
+#[test]
+fn negative_cells_fall_in_negative_chunks() {
+    assert_eq!(ChunkGrid::chunk(IVec2::new(0, 0)), IVec2::new(0, 0));
+    assert_eq!(ChunkGrid::chunk(IVec2::new(15, 15)), IVec2::new(0, 0));
+    assert_eq!(ChunkGrid::chunk(IVec2::new(16, 0)), IVec2::new(1, 0));
+    assert_eq!(ChunkGrid::chunk(IVec2::new(-1, -16)), IVec2::new(-1, -1));
+    assert_eq!(ChunkGrid::chunk(IVec2::new(-17, 0)), IVec2::new(-2, 0));
+}
+
+#[test]
+fn cell_rect_max_is_exclusive() {
+    let inside = IRect::new(0, 0, 16, 16);
+    assert_eq!(ChunkGrid::chunks_of_cells(inside), IRect::new(0, 0, 0, 0));
+    let across = IRect::new(10, 0, 20, 1);
+    assert_eq!(ChunkGrid::chunks_of_cells(across), IRect::new(0, 0, 1, 0));
+}
+
+#[test]
+fn world_area_maps_to_chunks() {
+    let chunk_size = (ChunkGrid::CHUNK_CELLS * TileAtlas::TILE_SIZE.as_ivec2()).as_vec2();
+    let area = Rect::from_corners(Vec2::splat(1.0), chunk_size * 1.5);
+    assert_eq!(ChunkGrid::chunks_of_area(area), IRect::new(0, 0, 1, 1));
+}
+
+#[test]
+fn iter_covers_inclusive_range() {
+    let chunks = ChunkGrid::iter(IRect::new(-1, 0, 1, 1)).collect::<Vec<_>>();
+    assert_eq!(chunks.len(), 6);
+    assert!(chunks.contains(&IVec2::new(-1, 0)));
+    assert!(chunks.contains(&IVec2::new(1, 1)));
+}
+
+#[test]
+fn layer_tiles_are_grouped_by_chunk() {
+    let layer = TileLayer::collision().with_data(vec![
+        descriptor(IVec2::new(0, 0), "wall"),
+        descriptor(IVec2::new(15, 3), "trap"),
+        descriptor(IVec2::new(16, 3), "wall"),
+        descriptor(IVec2::new(33, 17), "pass"),
+    ]);
+    let chunks = layer.chunks();
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks[&IVec2::new(0, 0)].len(), 2);
+    assert_eq!(chunks[&IVec2::new(1, 0)].len(), 1);
+    assert_eq!(chunks[&IVec2::new(2, 1)][0].kind, "pass");
+}
@@ -0,0 +1,233 @@
+use sara_level::prelude::*;
+
+mod common;
+
+fn shipped(id: usize) -> Vec<u8> {
+    std::fs::read(LevelAsset::file_path(id, LevelFormat::Binary)).unwrap()
+}
+
+#[test]
+fn headerless_levels_migrate_onto_the_collision_layer() {
+    let bytes = shipped(0);
+    assert_eq!(LevelCodec::version(&bytes).unwrap(), 0);
+    let level = LevelAsset::decode(&bytes, LevelFormat::Binary).unwrap();
+    let names = level
+        .layers
+        .iter()
+        .map(|layer| layer.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            TileLayer::BACKGROUND,
+            TileLayer::COLLISION,
+            TileLayer::FOREGROUND
+        ]
+    );
+    let collision = level.layer(TileLayer::COLLISION).unwrap();
+    assert!(collision.collider);
+    assert_eq!(collision.data.len(), level.tiles().count());
+}
+
+#[test]
+fn broken_text_reports_the_current_layout_error() {
+    let error = LevelAsset::decode(b"{\"rows\": 1}", LevelFormat::Text).unwrap_err();
+    assert!(error.to_string().contains("cols"), "{error}");
+}
+
+#[test]
+fn migrated_tiles_keep_their_kind_sprite() {
+    let registry = common::registry();
+    let level = LevelAsset::decode(&shipped(0), LevelFormat::Binary).unwrap();
+    assert!(level.tiles().all(|descriptor| {
+        registry
+            .get(&descriptor.kind)
+            .is_some_and(|definition| definition.frames.0 == descriptor.atlas_index)
+    }));
+}
+
+#[test]
+fn every_field_survives_round_trips() {
+    let registry = common::registry();
+    let mut tile = TileDescriptor::new((16.0, 16.0), registry.get("wall").unwrap(), 0.0);
+    tile.atlas_index = 77;
+    let level = || LevelAsset {
+        entry: (16.0, 16.0),
+        ..LevelAsset::new(4, 6)
+    };
+    let platform = MovingPlatform {
+        waypoints: vec![(0.0, 0.0), (64.0, 0.0), (64.0, 64.0)],
+        speed: 32.0,
+        wait: 1.0,
+        mode: PathMode::PingPong,
+        ..MovingPlatform::new("platform", 21)
+    };
+    let levels = [
+        (
+            "shipped",
+            LevelAsset::decode(&shipped(0), LevelFormat::Binary).unwrap(),
+        ),
+        (
+            "atlas index",
+            LevelAsset {
+                layers: vec![TileLayer::collision().with_data(vec![tile])],
+                ..level()
+            },
+        ),
+        (
+            "next",
+            LevelAsset {
+                next: Some(2),
+                ..level()
+            },
+        ),
+        (
+            "platforms",
+            LevelAsset {
+                platforms: vec![platform],
+                ..level()
+            },
+        ),
+        (
+            "enemies",
+            LevelAsset {
+                enemies: vec![
+                    EnemySpawn::new((48.0, 16.0), EnemyKind::WOLF),
+                    EnemySpawn::new((80.0, 96.0), EnemyKind::BAT.next()),
+                ],
+                ..level()
+            },
+        ),
+        (
+            "checkpoints",
+            LevelAsset {
+                checkpoints: vec![(48.0, 16.0), (144.0, 80.0)],
+                ..level()
+            },
+        ),
+        (
+            "bounds",
+            LevelAsset {
+                kill_depth: 160.0,
+                side_walls: false,
+                ..level()
+            },
+        ),
+        (
+            "abilities",
+            LevelAsset {
+                abilities: AbilitySet::from_iter([Ability::WallJump, Ability::AirDash]),
+                ..level()
+            },
+        ),
+        (
+            "pickups",
+            LevelAsset {
+                pickups: vec![
+                    AbilityPickup::new((48.0, 16.0), Ability::DoubleJump),
+                    AbilityPickup::new((80.0, 48.0), Ability::GroundDash),
+                ],
+                ..level()
+            },
+        ),
+        (
+            "gravity",
+            LevelAsset {
+                gravity: Some(150.0),
+                ..level()
+            },
+        ),
+    ];
+    for (name, level) in levels {
+        let bytes = level.encode(LevelFormat::Binary).unwrap();
+        assert_eq!(LevelCodec::version(&bytes).unwrap(), LevelCodec::VERSION);
+        for format in [LevelFormat::Binary, LevelFormat::Text] {
+            let decoded = LevelAsset::decode(&level.encode(format).unwrap(), format).unwrap();
+            assert_eq!(decoded, level, "{name} in {}", format.name());
+        }
+    }
+}
+
+#[test]
+fn newer_versions_are_unsupported() {
+    let mut bytes = Vec::from(LevelCodec::MAGIC);
+    bytes.extend_from_slice(&(LevelCodec::VERSION + 1).to_le_bytes());
+    let error = LevelAsset::decode(&bytes, LevelFormat::Binary).unwrap_err();
+    assert!(
+        matches!(error, LevelAssetError::UnsupportedVersion(version) if version == LevelCodec::VERSION + 1),
+        "{error}"
+    );
+}
+
+#[test]
+fn short_headers_are_truncated() {
+    for len in LevelCodec::MAGIC.len()..LevelCodec::MAGIC.len() + size_of::<u32>() {
+        let bytes = [LevelCodec::MAGIC.as_slice(), &[1, 0, 0, 0]].concat();
+        let error = LevelCodec::version(&bytes[..len]).unwrap_err();
+        assert!(matches!(error, LevelAssetError::TruncatedHeader), "{error}");
+    }
+}
+
+#[test]
+fn files_without_the_magic_are_read_as_headerless() {
+    let level = LevelAsset::decode(&shipped(0), LevelFormat::Binary).unwrap();
+    let bytes = level.encode(LevelFormat::Binary).unwrap();
+    let payload = &bytes[LevelCodec::MAGIC.len() + size_of::<u32>()..];
+    assert_eq!(LevelCodec::version(payload).unwrap(), 0);
+    // 0xFF still leads, so a broken magic can not pass for a headerless level
+    let mut wrong = bytes.clone();
+    wrong[1] = b'X';
+    assert_eq!(LevelCodec::version(&wrong).unwrap(), 0);
+    assert!(LevelAsset::decode(&wrong, LevelFormat::Binary).is_err());
+}
+
+// the current text layout with one field swapped for `value`
+fn text_with(field: &str, value: serde_json::Value) -> Vec<u8> {
+    let text = LevelAsset::new(1, 1).encode(LevelFormat::Text).unwrap();
+    let mut json = serde_json::from_slice::<serde_json::Value>(&text).unwrap();
+    json[field] = value;
+    serde_json::to_vec(&json).unwrap()
+}
+
+#[test]
+fn malformed_text_fields_are_errors() {
+    let error =
+        LevelAsset::decode(&text_with("gravity", "abc".into()), LevelFormat::Text).unwrap_err();
+    assert!(matches!(error, LevelAssetError::JsonError(_)), "{error}");
+}
+
+#[test]
+fn unknown_text_fields_are_errors() {
+    let error =
+        LevelAsset::decode(&text_with("gravty", 150.0.into()), LevelFormat::Text).unwrap_err();
+    assert!(error.to_string().contains("gravty"), "{error}");
+    let layer = serde_json::json!([{
+        "name": TileLayer::COLLISION,
+        "z": 1.0,
+        "paralax": 1.0,
+        "collider": true,
+        "data": []
+    }]);
+    let error = LevelAsset::decode(&text_with("layers", layer), LevelFormat::Text).unwrap_err();
+    assert!(error.to_string().contains("paralax"), "{error}");
+}
+
+#[test]
+fn writing_one_format_removes_the_other() {
+    let dir = std::env::temp_dir().join(format!("sara-levels-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let level = LevelAsset::new(1, 1);
+    level.write_in(&dir, 3, LevelFormat::Binary).unwrap();
+    let edited = LevelAsset {
+        next: Some(4),
+        ..LevelAsset::new(1, 1)
+    };
+    edited.write_in(&dir, 3, LevelFormat::Text).unwrap();
+    assert_eq!(LevelAsset::locate_in(&dir, 3), Some(LevelFormat::Text));
+    assert!(!std::fs::exists(dir.join(LevelAsset::file_name(3, LevelFormat::Binary))).unwrap());
+    let pack = LevelPack::load(&dir).unwrap();
+    assert_eq!(pack.next(3), Some(4));
+    level.write_in(&dir, 3, LevelFormat::Binary).unwrap();
+    assert!(!std::fs::exists(dir.join(LevelAsset::file_name(3, LevelFormat::Text))).unwrap());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
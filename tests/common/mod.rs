@@ -0,0 +1,7 @@
+use sara_level::prelude::*;
+use std::path::Path;
+
+// the tile definitions the game ships with
+pub fn registry() -> TileRegistry {
+    TileRegistry::read(Path::new(&TileRegistry::file_path())).unwrap()
+}
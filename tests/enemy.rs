@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+use sara_level::prelude::*;
+
+mod common;
+
+fn level(enemies: Vec<EnemySpawn>) -> LevelAsset {
+    LevelAsset {
+        entry: (16.0, 16.0),
+        enemies,
+        ..LevelAsset::new(4, 4)
+    }
+}
+
+#[test]
+fn sine_bats_weave_around_their_spawn() {
+    let bat = EnemySpawn::new((64.0, 64.0), EnemyKind::BAT);
+    let EnemyKind::Bat { range, speed, .. } = bat.kind else {
+        unreachable!();
+    };
+    assert_eq!(bat.flight_position_at(0.0), Vec2::new(64.0, 64.0));
+    assert!((bat.flight_velocity_at(0.0).x - speed).abs() < 1e-4);
+    // a quarter turn later it is at the far end of its range, standing still sideways
+    let quarter = std::f32::consts::FRAC_PI_2 * range / speed;
+    let position = bat.flight_position_at(quarter);
+    assert!((position - Vec2::new(64.0 + range, 64.0)).length() < 1e-3);
+    assert!(bat.flight_velocity_at(quarter).x.abs() < 1e-3);
+}
+
+#[test]
+fn only_sine_bats_leave_their_spawn() {
+    for kind in [EnemyKind::WOLF, EnemyKind::BAT.next()] {
+        let enemy = EnemySpawn::new((32.0, 32.0), kind);
+        assert_eq!(enemy.flight_position_at(1.0), Vec2::new(32.0, 32.0));
+        assert_eq!(enemy.flight_velocity_at(1.0), Vec2::ZERO);
+    }
+}
+
+#[test]
+fn kinds_cycle_through_both_bat_patterns() {
+    let kinds = std::iter::successors(Some(EnemyKind::default()), |kind| Some(kind.next()))
+        .take(4)
+        .map(|kind| match kind {
+            EnemyKind::Wolf { .. } => "wolf",
+            EnemyKind::Bat { pattern, .. } => pattern.name(),
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, ["wolf", "sine", "dive", "wolf"]);
+}
+
+#[test]
+fn broken_spawns_are_reported() {
+    let registry = common::registry();
+    let mut slow = EnemySpawn::new((48.0, 48.0), EnemyKind::BAT);
+    if let EnemyKind::Bat { speed, .. } = &mut slow.kind {
+        *speed = 0.0;
+    }
+    let level = level(vec![EnemySpawn::new((-16.0, 48.0), EnemyKind::WOLF), slow]);
+    let issues = level.validate(&LevelAsset::data_dir(), &registry);
+    assert!(issues.contains(&LevelIssue::EnemyOutOfBounds {
+        index: 0,
+        kind: "wolf",
+        cell: IVec2::new(-1, 1),
+    }));
+    assert!(issues.contains(&LevelIssue::InvalidEnemy {
+        index: 1,
+        kind: "bat"
+    }));
+}
@@ -0,0 +1,37 @@
+use sara_level::prelude::*;
+
+mod common;
+
+fn issues(layers: Vec<TileLayer>) -> Vec<LevelIssue> {
+    let level = LevelAsset {
+        layers,
+        entry: (16.0, 16.0),
+        ..LevelAsset::new(4, 4)
+    };
+    level.validate(&LevelAsset::data_dir(), &common::registry())
+}
+
+#[test]
+fn collider_layers_move_with_the_world() {
+    let collision = TileLayer::new(TileLayer::COLLISION, 1.0, 0.5, true);
+    assert!(
+        issues(vec![collision]).contains(&LevelIssue::ColliderParallax {
+            layer: TileLayer::COLLISION.to_string(),
+            parallax: 0.5,
+        })
+    );
+    let extra = TileLayer::new("walls", 1.5, 0.8, true);
+    assert!(issues(vec![TileLayer::collision(), extra]).iter().any(
+        |issue| matches!(issue, LevelIssue::ColliderParallax { layer, .. } if layer == "walls")
+    ));
+}
+
+#[test]
+fn decorative_layers_may_lag_behind() {
+    let background = TileLayer::new(TileLayer::BACKGROUND, 0.5, 0.5, false);
+    assert!(
+        !issues(vec![background, TileLayer::collision()])
+            .iter()
+            .any(|issue| matches!(issue, LevelIssue::ColliderParallax { .. }))
+    );
+}
@@ -0,0 +1,97 @@
+use sara_level::prelude::*;
+use std::path::Path;
+
+mod common;
+
+#[test]
+fn the_shipped_profile_is_the_default() {
+    let profile = MovementProfile::read(Path::new(&MovementProfile::file_path())).unwrap();
+    assert_eq!(profile, MovementProfile::DEFAULT);
+}
+
+#[test]
+fn missing_fields_keep_their_default() {
+    let profile = MovementProfile::decode(br#"{ "walk_speed": 90.0 }"#).unwrap();
+    assert_eq!(profile.walk_speed, 90.0);
+    assert_eq!(profile.jump_speed, MovementProfile::DEFAULT.jump_speed);
+}
+
+#[test]
+fn unknown_fields_are_rejected() {
+    let json = r#"{ "jump_cut_factr": 0.5 }"#;
+    match MovementProfile::decode(json.as_bytes()) {
+        Err(LevelAssetError::JsonError(error)) => {
+            assert!(error.to_string().contains("jump_cut_factr"), "{error}")
+        }
+        other => panic!("{json} gave {other:?}"),
+    }
+}
+
+#[test]
+fn out_of_range_fields_are_rejected() {
+    for (json, field) in [
+        (r#"{ "gravity": 0.0 }"#, "gravity"),
+        (r#"{ "fall_gravity_scale": -1.0 }"#, "fall_gravity_scale"),
+        (r#"{ "jump_cut": 1.5 }"#, "jump_cut"),
+    ] {
+        match MovementProfile::decode(json.as_bytes()) {
+            Err(LevelAssetError::InvalidMovement(invalid)) => assert_eq!(invalid, field),
+            other => panic!("{json} gave {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn falling_pulls_harder_than_rising() {
+    let profile = MovementProfile::DEFAULT;
+    assert_eq!(profile.gravity_scale(10.0), profile.rise_gravity_scale);
+    assert_eq!(profile.gravity_scale(-10.0), profile.fall_gravity_scale);
+}
+
+#[test]
+fn letting_go_early_cuts_the_jump() {
+    let profile = MovementProfile {
+        jump_cut: 0.5,
+        ..MovementProfile::DEFAULT
+    };
+    let kept = profile.jump_speed * 0.5;
+    assert_eq!(profile.cut_jump(profile.jump_speed), kept);
+    // a jump already slower than the cut, or falling, is left alone
+    assert_eq!(profile.cut_jump(kept / 2.0), kept / 2.0);
+    assert_eq!(profile.cut_jump(-20.0), -20.0);
+}
+
+#[test]
+fn levels_can_override_gravity() {
+    let json = r#"{
+        "rows": 2,
+        "cols": 2,
+        "layers": [],
+        "entry": [16.0, 16.0],
+        "next": null,
+        "platforms": [],
+        "enemies": [],
+        "checkpoints": [],
+        "kill_depth": 64.0,
+        "side_walls": true,
+        "abilities": [],
+        "pickups": []
+    }"#;
+    let mut level = LevelAsset::decode(json.as_bytes(), LevelFormat::Text).unwrap();
+    assert_eq!(level.gravity, None);
+    level.gravity = Some(-1.0);
+    assert!(
+        level
+            .validate(&LevelAsset::data_dir(), &common::registry())
+            .contains(&LevelIssue::InvalidGravity(-1.0))
+    );
+}
+
+#[test]
+fn a_stop_speed_above_the_start_speed_is_rejected() {
+    let json = r#"{ "walk_start_speed": 10.0, "walk_stop_speed": 20.0 }"#;
+    match MovementProfile::decode(json.as_bytes()) {
+        Err(LevelAssetError::InvalidMovement(field)) => assert_eq!(field, "walk_stop_speed"),
+        other => panic!("{json} gave {other:?}"),
+    }
+}
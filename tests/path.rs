@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+use sara_level::prelude::*;
+
+fn platform(mode: PathMode) -> MovingPlatform {
+    MovingPlatform {
+        waypoints: vec![(0.0, 0.0), (64.0, 0.0), (64.0, 64.0)],
+        speed: 32.0,
+        wait: 1.0,
+        mode,
+        ..MovingPlatform::new("platform", 21)
+    }
+}
+
+#[test]
+fn loop_returns_to_the_first_waypoint() {
+    let platform = platform(PathMode::Loop);
+    let closing = 64.0 * 2f32.sqrt() / 32.0;
+    assert!((platform.period() - (3.0 + 2.0 + 2.0 + closing)).abs() < 1e-4);
+    assert_eq!(platform.position_at(0.5), Vec2::ZERO);
+    assert_eq!(platform.velocity_at(0.5), Vec2::ZERO);
+    assert_eq!(platform.position_at(2.0), Vec2::new(32.0, 0.0));
+    assert_eq!(platform.velocity_at(2.0), Vec2::new(32.0, 0.0));
+    assert_eq!(platform.velocity_at(4.0), Vec2::new(0.0, 32.0));
+    let back = platform.velocity_at(7.5);
+    assert!(back.x < 0.0 && back.y < 0.0);
+    assert_eq!(platform.position_at(platform.period()), Vec2::ZERO);
+}
+
+#[test]
+fn ping_pong_walks_back_along_the_waypoints() {
+    let platform = platform(PathMode::PingPong);
+    assert!((platform.period() - (4.0 + 8.0)).abs() < 1e-4);
+    assert_eq!(platform.legs().len(), 4);
+    assert_eq!(platform.velocity_at(7.0), Vec2::new(0.0, -32.0));
+    assert_eq!(platform.velocity_at(10.0), Vec2::new(-32.0, 0.0));
+    assert_eq!(platform.position_at(11.0), Vec2::new(32.0, 0.0));
+}
+
+#[test]
+fn invalid_paths_stay_on_their_start() {
+    let mut platform = platform(PathMode::Loop);
+    platform.waypoints.truncate(1);
+    assert!(!platform.is_valid());
+    assert_eq!(platform.position_at(3.0), Vec2::ZERO);
+    assert_eq!(platform.velocity_at(3.0), Vec2::ZERO);
+    let mut platform = self::platform(PathMode::Loop);
+    platform.speed = 0.0;
+    assert!(!platform.is_valid());
+}
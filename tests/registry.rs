@@ -0,0 +1,149 @@
+use sara_level::prelude::*;
+
+mod common;
+
+#[test]
+fn shipped_definitions_cover_the_builtin_kinds() {
+    let registry = common::registry();
+    let wall = registry.get("wall").unwrap();
+    assert_eq!(wall.behavior, TileBehavior::Solid);
+    assert!(wall.is_mergeable());
+    let pass = registry.get("pass").unwrap();
+    assert_eq!(pass.behavior, TileBehavior::Exit);
+    assert!(pass.sensor && pass.is_animated());
+    let trap = registry.get("trap").unwrap();
+    assert_eq!(trap.behavior, TileBehavior::Damage);
+    assert!(!trap.is_mergeable());
+    assert!(registry.get("spike").unwrap().damage > trap.damage);
+}
+
+#[test]
+fn definitions_stay_inside_the_atlas() {
+    for definition in common::registry().tiles {
+        assert!(
+            definition.frames.0 <= definition.frames.1,
+            "{}",
+            definition.name
+        );
+        assert!(definition.frames.1 < TileAtlas::LEN, "{}", definition.name);
+    }
+}
+
+#[test]
+fn missing_fields_use_defaults() {
+    let json = r#"{ "tiles": [{ "name": "cloud", "frames": [5, 5] }] }"#;
+    let registry = TileRegistry::decode(json.as_bytes()).unwrap();
+    let cloud = registry.get("cloud").unwrap();
+    assert!(cloud.collider.is_none());
+    assert_eq!(cloud.behavior, TileBehavior::None);
+    assert!(!cloud.sensor);
+    assert_eq!(cloud.damage, 1);
+}
+
+#[test]
+fn unknown_fields_are_rejected() {
+    for json in [
+        r#"{ "tiles": [{ "name": "cloud", "frames": [5, 5], "damgae": 2 }] }"#,
+        r#"{ "tiles": [{
+            "name": "cloud",
+            "frames": [5, 5],
+            "collider": { "rectangle": { "width": 32.0, "height": 16.0, "damgae": 2 } }
+        }] }"#,
+        r#"{ "tiles": [], "damgae": 2 }"#,
+    ] {
+        let error = TileRegistry::decode(json.as_bytes()).unwrap_err();
+        assert!(error.to_string().contains("damgae"), "{error}");
+    }
+}
+
+#[test]
+fn duplicate_names_are_rejected() {
+    let json = r#"{ "tiles": [
+        { "name": "cloud", "frames": [5, 5] },
+        { "name": "wall", "frames": [0, 0] },
+        { "name": "cloud", "frames": [6, 6] }
+    ] }"#;
+    match TileRegistry::decode(json.as_bytes()) {
+        Err(LevelAssetError::DuplicateTileName(name)) => assert_eq!(name, "cloud"),
+        other => panic!("{other:?}"),
+    }
+}
+
+#[test]
+fn partial_tiles_are_never_merged() {
+    let registry = common::registry();
+    let tile = |x: f32, kind: &str, rotation: f32| {
+        TileDescriptor::new((x, 16.0), registry.get(kind).unwrap(), rotation)
+    };
+    let level = LevelAsset {
+        layers: vec![TileLayer::collision().with_data(vec![
+            tile(16.0, "slope", 0.0),
+            tile(48.0, "slope", std::f32::consts::FRAC_PI_2),
+            tile(80.0, "ledge", 0.0),
+            tile(112.0, "ledge", 0.0),
+        ])],
+        ..LevelAsset::new(1, 4)
+    };
+    let (merged, single) = level.tile_colliders(&registry);
+    assert!(merged.is_empty());
+    assert_eq!(single.len(), 4);
+    assert_eq!(single[1].1.rotation, std::f32::consts::FRAC_PI_2);
+}
+
+#[test]
+fn ledge_covers_the_top_half_of_its_cell() {
+    let ledge = common::registry()
+        .get("ledge")
+        .unwrap()
+        .collider
+        .clone()
+        .unwrap();
+    let vertices = ledge.vertices();
+    assert_eq!(vertices.len(), 4);
+    assert!(vertices.iter().all(|v| v.x.abs() == 16.0));
+    assert!(vertices.iter().all(|v| v.y == 0.0 || v.y == 16.0));
+}
+
+#[test]
+fn degenerate_shapes_are_rejected() {
+    let json = r#"{ "tiles": [{
+        "name": "line",
+        "frames": [5, 5],
+        "collider": { "polygon": { "points": [[0.0, 0.0], [8.0, 8.0], [16.0, 16.0]] } }
+    }] }"#;
+    let error = TileRegistry::decode(json.as_bytes()).unwrap_err();
+    assert!(error.to_string().contains("line"), "{error}");
+    let flat = TileShape::Rectangle {
+        width: 32.0,
+        height: 0.0,
+        offset: (0.0, 0.0),
+    };
+    assert!(!flat.is_valid());
+}
+
+#[test]
+fn platforms_stay_separate_bodies_on_their_own_layer() {
+    let platform = common::registry().get("platform").unwrap().clone();
+    assert_eq!(platform.behavior, TileBehavior::Platform);
+    assert_eq!(platform.layers, [GameCollisionLayers::Platform]);
+    assert!(!platform.sensor && !platform.is_mergeable());
+}
+
+#[test]
+fn breakable_tiles_keep_one_body_per_cell() {
+    let registry = common::registry();
+    let brick = registry.get("brick").unwrap();
+    assert_eq!(brick.behavior, TileBehavior::Breakable);
+    assert!(!brick.is_mergeable());
+    let descriptors = (0..3)
+        .map(|x| TileDescriptor::new((16.0 + 32.0 * x as f32, 16.0), brick, 0.0))
+        .collect::<Vec<_>>();
+    let level = LevelAsset {
+        layers: vec![TileLayer::collision().with_data(descriptors)],
+        entry: (16.0, 48.0),
+        ..LevelAsset::new(1, 3)
+    };
+    let (merged, single) = level.tile_colliders(&registry);
+    assert!(merged.is_empty());
+    assert_eq!(single.len(), 3);
+}
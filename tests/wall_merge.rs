@@ -0,0 +1,188 @@
+use bevy::prelude::*;
+use sara_level::prelude::*;
+use std::collections::{BTreeSet, HashSet};
+
+mod common;
+
+fn layout(rows: &[&str]) -> Vec<IVec2> {
+    // first string is the top row, '#' marks a wall
+    rows.iter()
+        .rev()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.chars()
+                .enumerate()
+                .filter(|(_, c)| *c == '#')
+                .map(move |(x, _)| IVec2::new(x as i32, y as i32))
+        })
+        .collect()
+}
+
+fn assert_exact_cover(cells: &[IVec2], rects: &[IRect]) {
+    let expected = cells.iter().map(|c| (c.x, c.y)).collect::<BTreeSet<_>>();
+    let mut covered = BTreeSet::new();
+    for rect in rects {
+        assert!(rect.width() > 0 && rect.height() > 0, "empty rect {rect:?}");
+        for y in rect.min.y..rect.max.y {
+            for x in rect.min.x..rect.max.x {
+                assert!(covered.insert((x, y)), "cell ({x}, {y}) covered twice");
+            }
+        }
+    }
+    assert_eq!(covered, expected);
+}
+
+#[test]
+fn empty_layout_has_no_colliders() {
+    assert!(CellMerger::merge(Vec::new()).is_empty());
+}
+
+#[test]
+fn single_row_becomes_one_rect() {
+    let cells = layout(&["#####"]);
+    let rects = CellMerger::merge(cells.clone());
+    assert_eq!(rects, vec![IRect::new(0, 0, 5, 1)]);
+    assert_exact_cover(&cells, &rects);
+}
+
+#[test]
+fn solid_block_becomes_one_rect() {
+    let cells = layout(&["###", "###", "###"]);
+    let rects = CellMerger::merge(cells.clone());
+    assert_eq!(rects, vec![IRect::new(0, 0, 3, 3)]);
+    assert_exact_cover(&cells, &rects);
+}
+
+#[test]
+fn column_becomes_one_rect() {
+    let cells = layout(&["#", "#", "#", "#"]);
+    let rects = CellMerger::merge(cells.clone());
+    assert_eq!(rects, vec![IRect::new(0, 0, 1, 4)]);
+    assert_exact_cover(&cells, &rects);
+}
+
+#[test]
+fn l_shape_becomes_two_rects() {
+    let cells = layout(&["#...", "#...", "####"]);
+    let rects = CellMerger::merge(cells.clone());
+    assert_eq!(rects.len(), 2);
+    assert_exact_cover(&cells, &rects);
+}
+
+#[test]
+fn room_with_hole_is_covered_exactly() {
+    let cells = layout(&["######", "#....#", "#....#", "######"]);
+    let rects = CellMerger::merge(cells.clone());
+    assert_eq!(rects.len(), 4);
+    assert_exact_cover(&cells, &rects);
+}
+
+#[test]
+fn checkerboard_can_not_be_merged() {
+    let cells = layout(&["#.#.", ".#.#", "#.#."]);
+    let rects = CellMerger::merge(cells.clone());
+    assert_eq!(rects.len(), cells.len());
+    assert_exact_cover(&cells, &rects);
+}
+
+#[test]
+fn duplicate_cells_are_merged_once() {
+    let mut cells = layout(&["###"]);
+    cells.push(IVec2::new(1, 0));
+    let rects = CellMerger::merge(cells.clone());
+    assert_eq!(rects, vec![IRect::new(0, 0, 3, 1)]);
+}
+
+#[test]
+fn cells_area_matches_tile_centers() {
+    let area = TileAtlas::cells_area(IRect::new(1, 2, 4, 3));
+    assert_eq!(area, Rect::new(32.0, 64.0, 128.0, 96.0));
+    let descriptor =
+        TileDescriptor::new((48.0, 80.0), common::registry().get("wall").unwrap(), 0.0);
+    assert!(area.contains(Vec2::from(descriptor.tile_pos)));
+}
+
+#[test]
+fn rotated_walls_are_left_out_of_merging() {
+    let registry = common::registry();
+    let wall = |x: f32, rotation: f32| {
+        TileDescriptor::new((x, 16.0), registry.get("wall").unwrap(), rotation)
+    };
+    let level = LevelAsset {
+        layers: vec![TileLayer::collision().with_data(vec![
+            wall(16.0, 0.0),
+            wall(48.0, std::f32::consts::PI),
+            wall(80.0, -std::f32::consts::FRAC_PI_2),
+            wall(112.0, 0.3),
+        ])],
+        ..LevelAsset::new(1, 4)
+    };
+    let (merged, single) = level.tile_colliders(&registry);
+    assert_eq!(
+        merged.into_iter().map(|(_, rect)| rect).collect::<Vec<_>>(),
+        vec![IRect::new(0, 0, 3, 1)]
+    );
+    assert_eq!(single.len(), 1);
+}
+
+#[test]
+fn shipped_levels_merge_exactly() {
+    let registry = common::registry();
+    for id in 0..2 {
+        let bytes = std::fs::read(LevelAsset::file_path(id, LevelFormat::Binary)).unwrap();
+        let level = LevelAsset::decode(&bytes, LevelFormat::Binary).unwrap();
+        let cells = level
+            .collision_tiles()
+            .filter(|d| d.kind == "wall" && d.is_axis_aligned())
+            .map(TileDescriptor::cell)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let (merged, _) = level.tile_colliders(&registry);
+        let rects = merged
+            .into_iter()
+            .filter(|(definition, _)| definition.name == "wall")
+            .map(|(_, rect)| rect)
+            .collect::<Vec<_>>();
+        assert_exact_cover(&cells, &rects);
+        assert!(rects.len() < cells.len());
+    }
+}
+
+#[test]
+fn decorative_walls_get_no_colliders() {
+    let registry = common::registry();
+    let wall = |x: f32| TileDescriptor::new((x, 16.0), registry.get("wall").unwrap(), 0.0);
+    let level = LevelAsset {
+        layers: vec![
+            TileLayer::background().with_data(vec![wall(16.0), wall(48.0)]),
+            TileLayer::collision().with_data(vec![wall(80.0)]),
+            TileLayer::foreground().with_data(vec![wall(112.0)]),
+        ],
+        ..LevelAsset::new(1, 4)
+    };
+    let (merged, single) = level.tile_colliders(&registry);
+    assert_eq!(
+        merged.into_iter().map(|(_, rect)| rect).collect::<Vec<_>>(),
+        vec![IRect::new(2, 0, 3, 1)]
+    );
+    assert!(single.is_empty());
+}
+
+#[test]
+fn sensor_tiles_keep_their_own_colliders() {
+    let registry = common::registry();
+    let tile =
+        |x: f32, kind: &str| TileDescriptor::new((x, 16.0), registry.get(kind).unwrap(), 0.0);
+    let level = LevelAsset {
+        layers: vec![TileLayer::collision().with_data(vec![
+            tile(16.0, "trap"),
+            tile(48.0, "trap"),
+            tile(80.0, "pass"),
+        ])],
+        ..LevelAsset::new(1, 4)
+    };
+    let (merged, single) = level.tile_colliders(&registry);
+    assert!(merged.is_empty());
+    assert_eq!(single.len(), 3);
+}